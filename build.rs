@@ -1,9 +1,12 @@
 extern crate cc;
 
 fn main() {
-    cc::Build::new()
-        .file("src/libs/win_sys_api.c")
-        .compile("win_sys_api");
+    // win_sys_api.c は Windows の API に依存しているので Windows 以外ではビルドしない
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("windows") {
+        cc::Build::new()
+            .file("src/libs/win_sys_api.c")
+            .compile("win_sys_api");
+    }
     // バグる時があるので、ビルドしても内容が変わらない場合は消す
     println!("cargo:rerun-if-changed=src/libs/win_sys_api.c");
 }
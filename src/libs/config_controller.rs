@@ -0,0 +1,245 @@
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use tabled::{builder::Builder, settings::Style};
+
+use super::data_controller::{self, Settings};
+use super::error::JweError;
+use super::logger_control;
+use super::records::ConfigRecord;
+
+pub const SEARCH_ENGINES: [&str; 3] = ["DuckDuckGo", "Google", "Bing"];
+
+pub struct SettingKey {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub read_only: bool,
+}
+
+pub const KEYS: [SettingKey; 5] = [
+    SettingKey {
+        name: "version",
+        description: "Version of jwe that last wrote the settings file",
+        read_only: true,
+    },
+    SettingKey {
+        name: "browser",
+        description: "Browser path or Default",
+        read_only: false,
+    },
+    SettingKey {
+        name: "web_search",
+        description: "Search engine (DuckDuckGo, Google, Bing)",
+        read_only: false,
+    },
+    SettingKey {
+        name: "memory_warn_percent",
+        description: "Memory usage percent reported as warn by mem show --pressure",
+        read_only: false,
+    },
+    SettingKey {
+        name: "memory_critical_percent",
+        description: "Memory usage percent reported as critical by mem show --pressure",
+        read_only: false,
+    },
+];
+
+fn find_key(key: &str) -> Result<&'static SettingKey, JweError> {
+    KEYS.iter()
+        .find(|setting_key| setting_key.name == key)
+        .ok_or_else(|| {
+            let names: Vec<&str> = KEYS.iter().map(|setting_key| setting_key.name).collect();
+            JweError::InvalidInput(format!(
+                "Unknown setting {} (available: {})",
+                key,
+                names.join(", ")
+            ))
+        })
+}
+
+fn find_writable_key(key: &str) -> Result<&'static SettingKey, JweError> {
+    let setting_key = find_key(key)?;
+    if setting_key.read_only {
+        return Err(JweError::InvalidInput(format!(
+            "Setting {} is read only",
+            key
+        )));
+    }
+    Ok(setting_key)
+}
+
+pub fn get(settings: &Settings, key: &str) -> Result<String, JweError> {
+    let value = match find_key(key)?.name {
+        "version" => settings.version.clone(),
+        "browser" => settings.browser.clone(),
+        "web_search" => settings.web_search.clone(),
+        "memory_warn_percent" => settings.memory_warn_percent.to_string(),
+        "memory_critical_percent" => settings.memory_critical_percent.to_string(),
+        _ => unreachable!(),
+    };
+    Ok(value)
+}
+
+pub fn record(settings: &Settings, key: &str) -> Result<ConfigRecord, JweError> {
+    let setting_key = find_key(key)?;
+    Ok(ConfigRecord {
+        key: setting_key.name.to_string(),
+        value: get(settings, key)?,
+        default: get(&Settings::default(), key)?,
+        description: setting_key.description.to_string(),
+    })
+}
+
+pub fn list(settings: &Settings) -> Vec<ConfigRecord> {
+    KEYS.iter()
+        .filter_map(|setting_key| record(settings, setting_key.name).ok())
+        .collect()
+}
+
+pub fn table(records: &[ConfigRecord]) -> String {
+    let mut builder = Builder::default();
+    builder.push_record(["Key", "Value", "Default", "Description"]);
+    for record in records {
+        builder.push_record([
+            record.key.clone(),
+            record.value.clone(),
+            record.default.clone(),
+            record.description.clone(),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii_rounded());
+    table.to_string()
+}
+
+// 大文字小文字を区別せずに検索エンジン名を正規化する
+pub fn normalize_search_engine(value: &str) -> Result<String, JweError> {
+    SEARCH_ENGINES
+        .iter()
+        .find(|engine| engine.eq_ignore_ascii_case(value.trim()))
+        .map(|engine| engine.to_string())
+        .ok_or_else(|| {
+            JweError::InvalidInput(format!(
+                "Invalid search engine {} (available: {})",
+                value,
+                SEARCH_ENGINES.join(", ")
+            ))
+        })
+}
+
+// --warn-at などの clap の value_parser と config set の両方で使う
+pub fn parse_percent(value: &str) -> Result<f64, String> {
+    match value.trim().trim_end_matches('%').parse::<f64>() {
+        Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(percent),
+        _ => Err(format!(
+            "expected a percent between 0 and 100, got {}",
+            value
+        )),
+    }
+}
+
+pub fn check_memory_thresholds(warn: f64, critical: f64) -> Result<(), JweError> {
+    if warn > critical {
+        return Err(JweError::InvalidInput(format!(
+            "memory warn threshold {}% must not be above the critical threshold {}%",
+            warn, critical
+        )));
+    }
+    Ok(())
+}
+
+pub fn set(settings: &mut Settings, key: &str, value: &str) -> Result<(), JweError> {
+    match find_writable_key(key)?.name {
+        "browser" => {
+            let value = value.trim();
+            if value.is_empty() {
+                return Err(JweError::InvalidInput(
+                    "browser must not be empty".to_string(),
+                ));
+            }
+            settings.browser = value.to_string();
+        }
+        "web_search" => settings.web_search = normalize_search_engine(value)?,
+        "memory_warn_percent" => {
+            let percent = parse_percent(value)
+                .map_err(|e| JweError::InvalidInput(format!("{}: {}", key, e)))?;
+            check_memory_thresholds(percent, settings.memory_critical_percent)?;
+            settings.memory_warn_percent = percent;
+        }
+        "memory_critical_percent" => {
+            let percent = parse_percent(value)
+                .map_err(|e| JweError::InvalidInput(format!("{}: {}", key, e)))?;
+            check_memory_thresholds(settings.memory_warn_percent, percent)?;
+            settings.memory_critical_percent = percent;
+        }
+        _ => unreachable!(),
+    }
+
+    logger_control::log(
+        &format!("Config set {} = {}", key, value),
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
+
+pub fn unset(settings: &mut Settings, key: &str) -> Result<(), JweError> {
+    let defaults = Settings::default();
+    match find_writable_key(key)?.name {
+        "browser" => settings.browser = defaults.browser,
+        "web_search" => settings.web_search = defaults.web_search,
+        "memory_warn_percent" => settings.memory_warn_percent = defaults.memory_warn_percent,
+        "memory_critical_percent" => {
+            settings.memory_critical_percent = defaults.memory_critical_percent
+        }
+        _ => unreachable!(),
+    }
+
+    logger_control::log(
+        &format!("Config unset {}", key),
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
+
+fn default_editor() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "notepad"
+    } else {
+        "vi"
+    }
+}
+
+// $VISUAL > $EDITOR > OS ごとのデフォルトの順に使う
+pub fn edit(path: &Path) -> Result<(), JweError> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| default_editor().to_string());
+
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or(default_editor());
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| JweError::Platform(format!("Failed to start editor {}: {}", program, e)))?;
+
+    if !status.success() {
+        return Err(JweError::Platform(format!(
+            "Editor {} exited with {}",
+            program, status
+        )));
+    }
+
+    // 編集後に読めるかどうか確認する
+    data_controller::read_settings()?;
+
+    logger_control::log(
+        &format!("Config edited with {}", program),
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
@@ -1,219 +1,246 @@
 use std::collections::HashMap;
-use std::fs;
 
-use dirs;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::VERISON;
 
+use super::error::JweError;
 use super::logger_control;
+use super::store::Store;
 
+const SETTINGS_FILE: &str = "settings.toml";
+const FAVORITES_FILE: &str = "favorites.toml";
+const LAUNCHER_FILE: &str = "launcher.toml";
+const LAUNCHER_GROUPS_FILE: &str = "launcher_groups.toml";
+
+// 足りない項目は Default の値で埋める
 #[derive(Deserialize, Serialize, Debug)]
+#[serde(default)]
 pub struct Settings {
     pub version: String,
     pub browser: String,
     pub web_search: String,
+    // mem show --pressure の閾値 (使用率 %)
+    pub memory_warn_percent: f64,
+    pub memory_critical_percent: f64,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: VERISON.to_string(),
+            browser: "Default".to_string(),
+            web_search: "DuckDuckGo".to_string(),
+            memory_warn_percent: 80.0,
+            memory_critical_percent: 95.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Default)]
 pub struct Favorites {
     pub favorites: HashMap<String, String>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-pub struct Launcher {
-    pub launchers: HashMap<String, String>,
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LaunchMode {
+    // 起動したらすぐ戻る
+    #[default]
+    Detach,
+    // 終了するまで待って終了コードを確認する
+    Wait,
 }
 
-pub fn read_favorites() -> Favorites {
-    let local_data = dirs::data_local_dir().expect("Failed to get local app data directory");
-
-    let favorites_loc = local_data
-        .join("johma_windows_enhanced")
-        .join("favorites.toml");
-
-    let contents =
-        fs::read_to_string(favorites_loc).expect("Something went wrong reading the file");
-
-    toml::from_str(&contents).expect("Failed to parse favorites file")
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct LauncherEntry {
+    pub program: String,
+    pub args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    pub env: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub mode: LaunchMode,
+    pub elevated: bool,
 }
 
-pub fn init_favorites() {
-    let local_data = dirs::data_local_dir().expect("Failed to get local app data directory");
-
-    let favorites_loc = local_data
-        .join("johma_windows_enhanced")
-        .join("favorites.toml");
-
-    if !favorites_loc.exists() {
-        let favorites = Favorites {
-            favorites: HashMap::new(),
-        };
-
-        let toml = toml::to_string(&favorites).expect("Failed to serialize favorites");
-
-        fs::write(favorites_loc, toml).expect("Failed to write favorites file");
-
-        logger_control::log("Create new favorites file", logger_control::LogLevel::INFO);
+impl LauncherEntry {
+    pub fn new(program: String) -> Self {
+        LauncherEntry {
+            program,
+            ..Default::default()
+        }
     }
-
-    logger_control::log(
-        "Favorites file already exists",
-        logger_control::LogLevel::INFO,
-    );
 }
 
-pub fn write_favorites(favorites: Favorites) {
-    let local_data = dirs::data_local_dir().expect("Failed to get local app data directory");
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct Launcher {
+    pub launchers: HashMap<String, LauncherEntry>,
+}
 
-    let favorites_loc = local_data
-        .join("johma_windows_enhanced")
-        .join("favorites.toml");
+fn default_timeout_secs() -> u64 {
+    30
+}
 
-    let toml = toml::to_string(&favorites).expect("Failed to serialize favorites");
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GroupItem {
+    pub launcher: String,
+    // 起動する前に待つ時間
+    #[serde(default)]
+    pub delay_ms: u64,
+    // プロセスが起動したのを確認してから次に進む
+    #[serde(default)]
+    pub wait_started: bool,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
 
-    fs::write(favorites_loc, toml).expect("Failed to write favorites file");
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct LauncherGroup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub items: Vec<GroupItem>,
+}
 
-    logger_control::log("Write favorites file", logger_control::LogLevel::INFO);
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct LauncherGroups {
+    pub groups: HashMap<String, LauncherGroup>,
 }
 
-pub fn init_launcher() -> Result<String, Box<dyn std::error::Error>> {
-    let local_data = dirs::data_local_dir().expect("Failed to get local app data directory");
+pub fn settings_store() -> Result<Store<Settings>, JweError> {
+    Store::new(SETTINGS_FILE)
+}
 
-    let launcher_loc = local_data
-        .join("johma_windows_enhanced")
-        .join("launcher.toml");
+// マイグレーション用に型を付けずに読み書きする
+pub fn raw_settings_store() -> Result<Store<toml::Table>, JweError> {
+    Store::new(SETTINGS_FILE)
+}
 
-    if !launcher_loc.exists() {
-        let launchers = Launcher {
-            launchers: HashMap::new(),
-        };
+pub fn favorites_store() -> Result<Store<Favorites>, JweError> {
+    Store::new(FAVORITES_FILE)
+}
 
-        let toml = toml::to_string(&launchers).expect("Failed to serialize launchers");
+pub fn launcher_store() -> Result<Store<Launcher>, JweError> {
+    Store::new(LAUNCHER_FILE)
+}
 
-        fs::write(launcher_loc, toml).expect("Failed to write launchers file");
+pub fn launcher_groups_store() -> Result<Store<LauncherGroups>, JweError> {
+    Store::new(LAUNCHER_GROUPS_FILE)
+}
 
-        logger_control::log("Create new launchers file", logger_control::LogLevel::INFO);
+pub fn raw_launcher_store() -> Result<Store<toml::Table>, JweError> {
+    Store::new(LAUNCHER_FILE)
+}
 
-        return Ok("Create new launcher file".to_string());
+fn init_store<T>(store: Store<T>, kind: &str) -> Result<bool, JweError>
+where
+    T: Serialize + serde::de::DeserializeOwned + Default,
+{
+    let created = store.init()?;
+
+    if created {
+        logger_control::log(
+            &format!("Create new {} file", kind),
+            logger_control::LogLevel::INFO,
+        );
+    } else {
+        logger_control::log(
+            &format!("{} file already exists", kind),
+            logger_control::LogLevel::INFO,
+        );
     }
 
-    logger_control::log(
-        "Launchers file already exists",
-        logger_control::LogLevel::INFO,
-    );
-
-    Ok("Launcher file already exists".to_string())
+    Ok(created)
 }
 
-pub fn read_launcher() -> Launcher {
-    let local_data = dirs::data_local_dir().expect("Failed to get local app data directory");
-
-    let launcher_loc = local_data
-        .join("johma_windows_enhanced")
-        .join("launcher.toml");
-
-    let contents = fs::read_to_string(launcher_loc).expect("Something went wrong reading the file");
+pub fn read_favorites() -> Result<Favorites, JweError> {
+    favorites_store()?.read()
+}
 
-    toml::from_str(&contents).expect("Failed to parse launchers file")
+pub fn init_favorites() -> Result<(), JweError> {
+    init_store(favorites_store()?, "Favorites").map(|_| ())
 }
 
-pub fn write_launcher(launchers: HashMap<String, String>) {
-    let local_data = dirs::data_local_dir().expect("Failed to get local app data directory");
+pub fn write_favorites(favorites: Favorites) -> Result<(), JweError> {
+    favorites_store()?.write(&favorites)?;
 
-    let launcher_loc = local_data
-        .join("johma_windows_enhanced")
-        .join("launcher.toml");
+    logger_control::log("Write favorites file", logger_control::LogLevel::INFO);
+    Ok(())
+}
 
-    let launchers_data = Launcher {
-        launchers: launchers,
-    };
+pub fn init_launcher() -> Result<String, JweError> {
+    if init_store(launcher_store()?, "Launchers")? {
+        Ok("Create new launcher file".to_string())
+    } else {
+        Ok("Launcher file already exists".to_string())
+    }
+}
 
-    let toml = toml::to_string(&launchers_data).expect("Failed to serialize launchers");
+pub fn read_launcher() -> Result<Launcher, JweError> {
+    launcher_store()?.read()
+}
 
-    fs::write(launcher_loc, toml).expect("Failed to write launchers file");
+pub fn write_launcher(launchers: HashMap<String, LauncherEntry>) -> Result<(), JweError> {
+    launcher_store()?.write(&Launcher { launchers })?;
 
     logger_control::log("Write launchers file", logger_control::LogLevel::INFO);
+    Ok(())
 }
 
-pub fn read_settings() -> Settings {
-    let local_data = dirs::data_local_dir().expect("Failed to get local app data directory");
-
-    let setting_loc = local_data
-        .join("johma_windows_enhanced")
-        .join("settings.toml");
-
-    let contents = fs::read_to_string(setting_loc).expect("Something went wrong reading the file");
-
-    toml::from_str(&contents).expect("Failed to parse settings file")
+pub fn init_launcher_groups() -> Result<(), JweError> {
+    init_store(launcher_groups_store()?, "Launcher groups").map(|_| ())
 }
 
-pub fn null_search_settings() {
-    let settings = read_settings();
-
-    if settings.version == "" {
-        let new_settings = Settings {
-            version: VERISON.to_string(),
-            browser: settings.browser,
-            web_search: settings.web_search,
-        };
-
-        write_settings(new_settings);
-    } else if settings.browser == "" {
-        let new_settings = Settings {
-            version: settings.version,
-            browser: "Default".to_string(),
-            web_search: settings.web_search,
-        };
-        write_settings(new_settings);
-    } else if settings.web_search == "" {
-        let new_settings = Settings {
-            version: settings.version,
-            browser: settings.browser,
-            web_search: "DuckDuckGo".to_string(),
-        };
-        write_settings(new_settings);
-    }
+pub fn read_launcher_groups() -> Result<LauncherGroups, JweError> {
+    launcher_groups_store()?.read()
 }
 
-pub fn init_settings() {
-    let local_data = dirs::data_local_dir().expect("Failed to get local app data directory");
-
-    let setting_loc = local_data
-        .join("johma_windows_enhanced")
-        .join("settings.toml");
-
-    if !setting_loc.exists() {
-        let settings = Settings {
-            version: VERISON.to_string(),
-            browser: "Default".to_string(),
-            web_search: "DuckDuckGo".to_string(),
-        };
+pub fn write_launcher_groups(groups: HashMap<String, LauncherGroup>) -> Result<(), JweError> {
+    launcher_groups_store()?.write(&LauncherGroups { groups })?;
 
-        let toml = toml::to_string(&settings).expect("Failed to serialize settings");
+    logger_control::log("Write launcher groups file", logger_control::LogLevel::INFO);
+    Ok(())
+}
 
-        fs::write(setting_loc, toml).expect("Failed to write settings file");
+pub fn read_settings() -> Result<Settings, JweError> {
+    settings_store()?.read()
+}
 
-        logger_control::log("Create new settings file", logger_control::LogLevel::INFO);
+// 手で空にされた値は、保存されているバージョンに関係なく起動のたびにデフォルトに戻す
+pub fn null_search_settings() -> Result<(), JweError> {
+    let mut settings = read_settings()?;
+    let defaults = Settings::default();
+
+    let mut repaired = false;
+    for (value, default) in [
+        (&mut settings.version, defaults.version),
+        (&mut settings.browser, defaults.browser),
+        (&mut settings.web_search, defaults.web_search),
+    ] {
+        if value.is_empty() {
+            *value = default;
+            repaired = true;
+        }
     }
 
-    logger_control::log(
-        "Settings file already exists",
-        logger_control::LogLevel::INFO,
-    );
+    if repaired {
+        write_settings(settings)?;
+    }
+    Ok(())
 }
 
-pub fn write_settings(settings: Settings) {
-    let local_data = dirs::data_local_dir().expect("Failed to get local app data directory");
-
-    let setting_loc = local_data
-        .join("johma_windows_enhanced")
-        .join("settings.toml");
-
-    let toml = toml::to_string(&settings).expect("Failed to serialize settings");
+pub fn init_settings() -> Result<(), JweError> {
+    init_store(settings_store()?, "Settings").map(|_| ())
+}
 
-    fs::write(setting_loc, toml).expect("Failed to write settings file");
+pub fn write_settings(settings: Settings) -> Result<(), JweError> {
+    settings_store()?.write(&settings)?;
 
     logger_control::log("Write settings file", logger_control::LogLevel::INFO);
+    Ok(())
 }
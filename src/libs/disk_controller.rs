@@ -0,0 +1,100 @@
+use sysinfo::Disks;
+use tabled::{builder::Builder, settings::Style};
+
+use super::error::JweError;
+use super::records::DiskRecord;
+use super::units;
+
+pub fn list(warn_below: Option<f64>) -> Vec<DiskRecord> {
+    let disks = Disks::new_with_refreshed_list();
+    let mut records: Vec<DiskRecord> = disks
+        .list()
+        .iter()
+        .map(|disk| {
+            let total = disk.total_space();
+            let available = disk.available_space();
+            let used = total.saturating_sub(available);
+            let used_percent = units::percent(used, total);
+
+            DiskRecord {
+                name: disk.name().to_string_lossy().to_string(),
+                mount_point: disk.mount_point().display().to_string(),
+                file_system: disk.file_system().to_string_lossy().to_string(),
+                kind: disk.kind().to_string(),
+                total,
+                used,
+                available,
+                used_percent,
+                removable: disk.is_removable(),
+                read_only: disk.is_read_only(),
+                // 読み取り専用のマウントは常に満杯なので対象外
+                low: warn_below.map(|percent| {
+                    total > 0 && !disk.is_read_only() && 100.0 - used_percent < percent
+                }),
+            }
+        })
+        .collect();
+    records.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    records
+}
+
+pub fn table(records: &[DiskRecord]) -> String {
+    let mut builder = Builder::default();
+    builder.push_record([
+        "Mount",
+        "Name",
+        "File system",
+        "Kind",
+        "Total",
+        "Used",
+        "Free",
+        "Used %",
+        "Removable",
+        "Read only",
+    ]);
+    for record in records {
+        let mut used_percent = format!("{:.1}%", record.used_percent);
+        if record.low == Some(true) {
+            used_percent.push_str(" LOW");
+        }
+        builder.push_record([
+            record.mount_point.clone(),
+            record.name.clone(),
+            record.file_system.clone(),
+            record.kind.clone(),
+            units::format_bytes(record.total),
+            units::format_bytes(record.used),
+            units::format_bytes(record.available),
+            used_percent,
+            record.removable.to_string(),
+            record.read_only.to_string(),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii_rounded());
+    table.to_string()
+}
+
+// 空き容量が閾値を下回ったディスクがあればエラーにする
+pub fn check(records: &[DiskRecord], warn_below: f64) -> Result<(), JweError> {
+    let low: Vec<String> = records
+        .iter()
+        .filter(|record| record.low == Some(true))
+        .map(|record| {
+            format!(
+                "{} ({:.1}% free)",
+                record.mount_point,
+                100.0 - record.used_percent
+            )
+        })
+        .collect();
+
+    if low.is_empty() {
+        return Ok(());
+    }
+    Err(JweError::Threshold(format!(
+        "Free space below {}% on {}",
+        warn_below,
+        low.join(", ")
+    )))
+}
@@ -0,0 +1,58 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum JweError {
+    Io(io::Error),
+    File(PathBuf, io::Error),
+    Parse(String),
+    NotFound(String),
+    InvalidInput(String),
+    Platform(String),
+    // disk show --warn-below などのヘルスチェックで閾値を超えた
+    Threshold(String),
+}
+
+impl JweError {
+    // スクリプトから呼ばれた時に判別できるように種類ごとに終了コードを分ける
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            JweError::InvalidInput(_) => 2,
+            JweError::NotFound(_) => 3,
+            JweError::Io(_) | JweError::File(_, _) => 4,
+            JweError::Parse(_) => 5,
+            JweError::Platform(_) => 6,
+            JweError::Threshold(_) => 7,
+        }
+    }
+}
+
+impl fmt::Display for JweError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JweError::Io(e) => write!(f, "{}", e),
+            JweError::File(path, e) => write!(f, "{}: {}", path.display(), e),
+            JweError::Parse(message) => write!(f, "{}", message),
+            JweError::NotFound(message) => write!(f, "{}", message),
+            JweError::InvalidInput(message) => write!(f, "{}", message),
+            JweError::Platform(message) => write!(f, "{}", message),
+            JweError::Threshold(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for JweError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JweError::Io(e) | JweError::File(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for JweError {
+    fn from(e: io::Error) -> Self {
+        JweError::Io(e)
+    }
+}
@@ -0,0 +1,286 @@
+use std::cmp::Ordering;
+use std::fs::{self, Metadata};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local};
+use clap::ValueEnum;
+use tabled::{
+    builder::Builder,
+    settings::{object::Cell, Color, Style},
+};
+
+use super::error::JweError;
+use super::output;
+use super::records::FileRecord;
+use super::units;
+
+#[cfg(target_os = "windows")]
+const FILE_ATTRIBUTE_READONLY: u32 = 0x1;
+#[cfg(target_os = "windows")]
+const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+#[cfg(target_os = "windows")]
+const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+#[cfg(target_os = "windows")]
+const FILE_ATTRIBUTE_ARCHIVE: u32 = 0x20;
+#[cfg(target_os = "windows")]
+const EXECUTABLE_EXTENSIONS: [&str; 5] = ["exe", "bat", "cmd", "com", "ps1"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum FileSort {
+    #[default]
+    Name,
+    Size,
+    Time,
+    Ext,
+}
+
+#[derive(Debug, Default)]
+pub struct ListOptions {
+    pub all: bool,
+    pub sort: FileSort,
+    pub reverse: bool,
+    pub dirs_first: bool,
+}
+
+struct Entry {
+    record: FileRecord,
+    hidden: bool,
+    modified: Option<SystemTime>,
+}
+
+pub fn kind(metadata: &Metadata) -> &'static str {
+    let file_type = metadata.file_type();
+    if file_type.is_symlink() {
+        "symlink"
+    } else if file_type.is_dir() {
+        "dir"
+    } else if file_type.is_file() {
+        "file"
+    } else {
+        "other"
+    }
+}
+
+// rwxr-xr-x の形式
+#[cfg(not(target_os = "windows"))]
+pub fn permissions(metadata: &Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = metadata.permissions().mode();
+    let mut text = String::with_capacity(9);
+    for shift in [6, 3, 0] {
+        let bits = mode >> shift;
+        text.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        text.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        text.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+    text
+}
+
+// PowerShell の Mode と同じ rahs の形式 (読み取り専用、アーカイブ、隠し、システム)
+#[cfg(target_os = "windows")]
+pub fn permissions(metadata: &Metadata) -> String {
+    use std::os::windows::fs::MetadataExt;
+
+    let attributes = metadata.file_attributes();
+    [
+        (FILE_ATTRIBUTE_READONLY, 'r'),
+        (FILE_ATTRIBUTE_ARCHIVE, 'a'),
+        (FILE_ATTRIBUTE_HIDDEN, 'h'),
+        (FILE_ATTRIBUTE_SYSTEM, 's'),
+    ]
+    .iter()
+    .map(|(flag, c)| if attributes & flag != 0 { *c } else { '-' })
+    .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn is_hidden(path: &Path, _metadata: &Metadata) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+#[cfg(target_os = "windows")]
+pub fn is_hidden(_path: &Path, metadata: &Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+
+    metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_executable(record: &FileRecord) -> bool {
+    record.kind == "file" && record.permissions.contains('x')
+}
+
+#[cfg(target_os = "windows")]
+fn is_executable(record: &FileRecord) -> bool {
+    record.kind == "file"
+        && Path::new(&record.name).extension().is_some_and(|ext| {
+            EXECUTABLE_EXTENSIONS
+                .iter()
+                .any(|executable| ext.eq_ignore_ascii_case(executable))
+        })
+}
+
+pub fn format_time(time: SystemTime) -> String {
+    DateTime::<Local>::from(time)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+pub fn not_found(path: &Path, e: std::io::Error) -> JweError {
+    if e.kind() == ErrorKind::NotFound {
+        JweError::NotFound(format!("Path not found {}", path.display()))
+    } else {
+        JweError::File(path.to_path_buf(), e)
+    }
+}
+
+pub fn record(path: &Path, metadata: &Metadata) -> FileRecord {
+    FileRecord {
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string()),
+        path: path.display().to_string(),
+        kind: kind(metadata).to_string(),
+        size: metadata.len(),
+        modified: metadata.modified().ok().map(format_time),
+        permissions: permissions(metadata),
+    }
+}
+
+// シンボリックリンクはリンク先ではなくリンク自体の情報を使う
+fn entry(path: PathBuf) -> Result<Entry, JweError> {
+    let metadata = fs::symlink_metadata(&path).map_err(|e| not_found(&path, e))?;
+
+    Ok(Entry {
+        hidden: is_hidden(&path, &metadata),
+        modified: metadata.modified().ok(),
+        record: record(&path, &metadata),
+    })
+}
+
+fn extension(record: &FileRecord) -> String {
+    if record.kind == "dir" {
+        return String::new();
+    }
+    Path::new(&record.name)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+// size と time は ls と同じく大きい順、新しい順
+fn compare(a: &Entry, b: &Entry, sort: FileSort) -> Ordering {
+    let by_name = a
+        .record
+        .name
+        .to_lowercase()
+        .cmp(&b.record.name.to_lowercase());
+    match sort {
+        FileSort::Name => by_name,
+        FileSort::Size => b.record.size.cmp(&a.record.size).then(by_name),
+        FileSort::Time => b.modified.cmp(&a.modified).then(by_name),
+        FileSort::Ext => extension(&a.record)
+            .cmp(&extension(&b.record))
+            .then(by_name),
+    }
+}
+
+// path がファイルならそのファイルだけを返す
+pub fn list(path: &Path, options: &ListOptions) -> Result<Vec<FileRecord>, JweError> {
+    let metadata = fs::metadata(path).map_err(|e| not_found(path, e))?;
+    let mut entries = if metadata.is_dir() {
+        fs::read_dir(path)
+            .map_err(|e| JweError::File(path.to_path_buf(), e))?
+            .flatten()
+            // 読んでいる間に消えたものなどは飛ばす
+            .filter_map(|dir_entry| entry(dir_entry.path()).ok())
+            .collect()
+    } else {
+        vec![entry(path.to_path_buf())?]
+    };
+
+    if !options.all {
+        entries.retain(|entry| !entry.hidden);
+    }
+    entries.sort_by(|a, b| {
+        let order = compare(a, b, options.sort);
+        if options.reverse {
+            order.reverse()
+        } else {
+            order
+        }
+    });
+    if options.dirs_first {
+        entries.sort_by_key(|entry| entry.record.kind != "dir");
+    }
+
+    Ok(entries.into_iter().map(|entry| entry.record).collect())
+}
+
+pub fn color(record: &FileRecord) -> Option<Color> {
+    match record.kind.as_str() {
+        "dir" => Some(Color::BOLD | Color::FG_BLUE),
+        "symlink" => Some(Color::FG_CYAN),
+        _ if is_executable(record) => Some(Color::FG_GREEN),
+        _ => None,
+    }
+}
+
+pub fn grid(records: &[FileRecord], full_path: bool) -> String {
+    let items: Vec<String> = records
+        .iter()
+        .map(|record| {
+            if full_path {
+                record.path.clone()
+            } else {
+                record.name.clone()
+            }
+        })
+        .collect();
+    let colors: Vec<Option<Color>> = if output::use_color() {
+        records.iter().map(color).collect()
+    } else {
+        Vec::new()
+    };
+    output::colored_grid(&items, &colors)
+}
+
+pub fn long_table(records: &[FileRecord], full_path: bool) -> String {
+    const NAME_COLUMN: usize = 4;
+
+    let mut builder = Builder::default();
+    builder.push_record(["Permissions", "Size", "Modified", "Type", "Name"]);
+    for record in records {
+        builder.push_record([
+            record.permissions.clone(),
+            if record.kind == "dir" {
+                "-".to_string()
+            } else {
+                units::format_bytes(record.size)
+            },
+            record.modified.clone().unwrap_or_default(),
+            record.kind.clone(),
+            if full_path {
+                record.path.clone()
+            } else {
+                record.name.clone()
+            },
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii_rounded());
+    if output::use_color() {
+        for (index, record) in records.iter().enumerate() {
+            if let Some(color) = color(record) {
+                // 1 行目は見出し
+                table.modify(Cell::new(index + 1, NAME_COLUMN), color);
+            }
+        }
+    }
+    table.to_string()
+}
@@ -0,0 +1,182 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use clap::ValueEnum;
+use glob::{MatchOptions, Pattern};
+use regex::{Regex, RegexBuilder};
+
+use super::error::JweError;
+use super::file_controller;
+use super::logger_control;
+use super::records::FileRecord;
+use super::tree::{self, WalkOptions};
+
+// 先頭にこれだけ読んで NUL があればバイナリとみなして中身は検索しない
+const BINARY_CHECK_BYTES: usize = 8000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FileKind {
+    File,
+    Dir,
+    Symlink,
+}
+
+impl FileKind {
+    fn name(self) -> &'static str {
+        match self {
+            FileKind::File => "file",
+            FileKind::Dir => "dir",
+            FileKind::Symlink => "symlink",
+        }
+    }
+}
+
+// ファイル名の比較はプロセス名と同じく大文字小文字を区別しない
+pub enum NamePattern {
+    Contains(String),
+    Glob(Pattern),
+    Regex(Regex),
+}
+
+pub fn parse_pattern(pattern: &str, regex: bool) -> Result<NamePattern, JweError> {
+    if regex {
+        return RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map(NamePattern::Regex)
+            .map_err(|e| JweError::InvalidInput(format!("Invalid regex {}: {}", pattern, e)));
+    }
+    if pattern.contains(['*', '?', '[']) {
+        return Pattern::new(pattern)
+            .map(NamePattern::Glob)
+            .map_err(|e| JweError::InvalidInput(format!("Invalid pattern {}: {}", pattern, e)));
+    }
+    Ok(NamePattern::Contains(pattern.to_lowercase()))
+}
+
+impl NamePattern {
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            NamePattern::Contains(text) => name.to_lowercase().contains(text),
+            NamePattern::Glob(pattern) => pattern.matches_with(
+                name,
+                MatchOptions {
+                    case_sensitive: false,
+                    ..MatchOptions::new()
+                },
+            ),
+            NamePattern::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+pub fn content_regex(pattern: &str, ignore_case: bool) -> Result<Regex, JweError> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| JweError::InvalidInput(format!("Invalid regex {}: {}", pattern, e)))
+}
+
+#[derive(Default)]
+pub struct FindOptions {
+    pub walk: WalkOptions,
+    pub kind: Option<FileKind>,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    // 最終更新がこの期間以内 / より前
+    pub newer: Option<Duration>,
+    pub older: Option<Duration>,
+    pub grep: Option<Regex>,
+}
+
+// 読めないファイルやバイナリは当たらなかった扱いにする
+fn contains_match(path: &Path, regex: &Regex) -> bool {
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    let mut head = vec![0; BINARY_CHECK_BYTES];
+    let Ok(read) = file.read(&mut head) else {
+        return false;
+    };
+    if head[..read].contains(&0) || file.rewind().is_err() {
+        return false;
+    }
+
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    while let Ok(read) = reader.read_until(b'\n', &mut line) {
+        if read == 0 {
+            break;
+        }
+        if regex.is_match(&String::from_utf8_lossy(&line)) {
+            return true;
+        }
+        line.clear();
+    }
+    false
+}
+
+fn modified_within(modified: Option<SystemTime>, duration: Duration) -> bool {
+    modified
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age <= duration)
+}
+
+pub fn find(
+    root: &Path,
+    pattern: &NamePattern,
+    options: &FindOptions,
+) -> Result<Vec<FileRecord>, JweError> {
+    tree::check_root(root)?;
+
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    for entry in tree::walker(root, &options.walk)?.build() {
+        let Ok(entry) = entry else {
+            skipped += 1;
+            continue;
+        };
+        if entry.depth() == 0 {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy();
+        if !pattern.matches(&name) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            skipped += 1;
+            continue;
+        };
+
+        let kind = file_controller::kind(&metadata);
+        let modified = metadata.modified().ok();
+        let matched = options.kind.is_none_or(|wanted| wanted.name() == kind)
+            && options.min_size.is_none_or(|min| metadata.len() >= min)
+            && options.max_size.is_none_or(|max| metadata.len() <= max)
+            && options
+                .newer
+                .is_none_or(|newer| modified_within(modified, newer))
+            && options
+                .older
+                .is_none_or(|older| !modified_within(modified, older))
+            && options
+                .grep
+                .as_ref()
+                .is_none_or(|regex| kind == "file" && contains_match(entry.path(), regex));
+
+        if matched {
+            records.push(file_controller::record(entry.path(), &metadata));
+        }
+    }
+
+    if skipped > 0 {
+        logger_control::log(
+            &format!("Find skipped {} entries that could not be read", skipped),
+            logger_control::LogLevel::WARNING,
+        );
+    }
+    records.sort_by_key(|record| record.path.to_lowercase());
+    Ok(records)
+}
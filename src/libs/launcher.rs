@@ -0,0 +1,249 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sysinfo::{Pid, Process, ProcessesToUpdate, System};
+
+use super::data_controller::{LaunchMode, LauncherEntry, LauncherGroup};
+use super::error::JweError;
+use super::logger_control;
+use super::platform::Platform;
+use super::records::GroupRunRecord;
+
+// --env KEY=VALUE 用
+pub fn parse_env(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got {}", value)),
+    }
+}
+
+// lc group add の LAUNCHER[:DELAY_MS[:TIMEOUT_SECS]] 用。省略した値は --delay と --timeout を使う
+#[derive(Debug, Clone)]
+pub struct GroupItemSpec {
+    pub launcher: String,
+    pub delay_ms: Option<u64>,
+    pub timeout_secs: Option<u64>,
+}
+
+pub fn parse_group_item(value: &str) -> Result<GroupItemSpec, String> {
+    let mut parts = value.split(':');
+    let launcher = parts.next().unwrap_or_default().to_string();
+    let mut number = |name: &str| match parts.next() {
+        None | Some("") => Ok(None),
+        Some(part) => part
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("invalid {} {} in {}", name, part, value)),
+    };
+    let delay_ms = number("delay")?;
+    let timeout_secs = number("timeout")?;
+
+    if launcher.is_empty() || parts.next().is_some() {
+        return Err(format!(
+            "expected LAUNCHER[:DELAY_MS[:TIMEOUT_SECS]], got {}",
+            value
+        ));
+    }
+    Ok(GroupItemSpec {
+        launcher,
+        delay_ms,
+        timeout_secs,
+    })
+}
+
+pub fn build_command(entry: &LauncherEntry) -> Command {
+    let mut command = Command::new(&entry.program);
+    command.args(&entry.args).envs(&entry.env);
+    if let Some(cwd) = &entry.cwd {
+        command.current_dir(cwd);
+    }
+    command
+}
+
+pub fn execute(name: &str, entry: &LauncherEntry, mut command: Command) -> Result<(), JweError> {
+    let result = match entry.mode {
+        LaunchMode::Detach => command.spawn().map(|_| ()),
+        LaunchMode::Wait => match command.status() {
+            Ok(status) if status.success() => Ok(()),
+            Ok(status) => {
+                logger_control::log(
+                    &format!("Launcher {} exited with {}", name, status),
+                    logger_control::LogLevel::ERROR,
+                );
+                return Err(JweError::Platform(format!(
+                    "Launcher {} exited with {}",
+                    name, status
+                )));
+            }
+            Err(e) => Err(e),
+        },
+    };
+
+    match result {
+        Ok(_) => {
+            logger_control::log(
+                &format!("Ran launcher: {} ({})", name, entry.program),
+                logger_control::LogLevel::INFO,
+            );
+            Ok(())
+        }
+        Err(e) => {
+            logger_control::log(
+                &format!("Failed to run launcher {}: {}", name, e),
+                logger_control::LogLevel::CRITICAL,
+            );
+            Err(JweError::Platform(format!(
+                "Failed to run launcher {} ({}): {}",
+                name, entry.program, e
+            )))
+        }
+    }
+}
+
+// 実行ファイル名 (拡張子なし) でプロセスを探す
+fn process_name_of(program: &str) -> String {
+    Path::new(program)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_lowercase())
+        .unwrap_or_else(|| program.to_lowercase())
+}
+
+// Linux の process.name() は comm なので 15 文字で切られている
+const COMM_LEN: usize = 15;
+
+// 実行ファイルのパスが取れればそれで比べ、取れなければプロセス名で比べる
+fn is_program(process: &Process, program: &str) -> bool {
+    let name = process_name_of(program);
+    if process
+        .exe()
+        .is_some_and(|exe| process_name_of(&exe.to_string_lossy()) == name)
+    {
+        return true;
+    }
+
+    let process_name = process.name().to_string_lossy().to_lowercase();
+    if process_name_of(&process_name) == name {
+        return true;
+    }
+    cfg!(target_os = "linux") && {
+        let file_name = Path::new(program)
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        file_name.chars().count() > COMM_LEN
+            && file_name.chars().take(COMM_LEN).collect::<String>() == process_name
+    }
+}
+
+fn matching_pids(sys: &System, program: &str) -> HashSet<Pid> {
+    sys.processes()
+        .iter()
+        .filter(|(_, process)| is_program(process, program))
+        .map(|(pid, _)| *pid)
+        .collect()
+}
+
+// 起動する前から動いている同じ名前のプロセスは数えないように、先に PID を控えておく
+pub fn running_pids(program: &str) -> HashSet<Pid> {
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+    matching_pids(&sys, program)
+}
+
+pub fn wait_until_started(program: &str, existing: &HashSet<Pid>, timeout: Duration) -> bool {
+    let started = Instant::now();
+    let mut sys = System::new();
+
+    loop {
+        sys.refresh_processes(ProcessesToUpdate::All, true);
+        if matching_pids(&sys, program)
+            .iter()
+            .any(|pid| !existing.contains(pid))
+        {
+            return true;
+        }
+        if started.elapsed() >= timeout {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+}
+
+// グループの中の 1 つが失敗しても残りは起動する
+pub fn run_group(
+    platform: &impl Platform,
+    group_name: &str,
+    group: &LauncherGroup,
+    launchers: &HashMap<String, LauncherEntry>,
+) -> Vec<GroupRunRecord> {
+    let mut records = Vec::new();
+
+    for (index, item) in group.items.iter().enumerate() {
+        if item.delay_ms > 0 {
+            thread::sleep(Duration::from_millis(item.delay_ms));
+        }
+
+        let result = match launchers.get(&item.launcher) {
+            None => Err(JweError::NotFound(format!(
+                "Launcher not found {}",
+                item.launcher
+            ))),
+            Some(entry) => {
+                // wait モードはもう終了まで待っているので、起動の確認はしない
+                let check_started = item.wait_started && entry.mode != LaunchMode::Wait;
+                let existing = if check_started {
+                    running_pids(&entry.program)
+                } else {
+                    HashSet::new()
+                };
+                platform.run_launcher(&item.launcher, entry).and_then(|_| {
+                    if check_started
+                        && !wait_until_started(
+                            &entry.program,
+                            &existing,
+                            Duration::from_secs(item.timeout_secs),
+                        )
+                    {
+                        return Err(JweError::Platform(format!(
+                            "{} did not start within {}s",
+                            entry.program, item.timeout_secs
+                        )));
+                    }
+                    Ok(())
+                })
+            }
+        };
+
+        let record = match result {
+            Ok(_) => GroupRunRecord {
+                step: index + 1,
+                launcher: item.launcher.clone(),
+                success: true,
+                message: "started".to_string(),
+            },
+            Err(e) => GroupRunRecord {
+                step: index + 1,
+                launcher: item.launcher.clone(),
+                success: false,
+                message: e.to_string(),
+            },
+        };
+        logger_control::log(
+            &format!(
+                "Launcher group {} step {} {}: {}",
+                group_name, record.step, record.launcher, record.message
+            ),
+            if record.success {
+                logger_control::LogLevel::INFO
+            } else {
+                logger_control::LogLevel::ERROR
+            },
+        );
+        records.push(record);
+    }
+
+    records
+}
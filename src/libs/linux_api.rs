@@ -0,0 +1,166 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use sysinfo::{Pid, ProcessesToUpdate, Signal, System};
+
+use crate::libs::data_controller::LauncherEntry;
+use crate::libs::error::JweError;
+use crate::libs::logger_control;
+use crate::libs::platform::Platform;
+
+// Windows の既知フォルダを XDG のディレクトリに対応させる
+pub struct LinuxPlatform;
+
+const TASK_MANAGERS: [&str; 4] = [
+    "gnome-system-monitor",
+    "plasma-systemmonitor",
+    "ksysguard",
+    "xfce4-taskmanager",
+];
+
+impl Platform for LinuxPlatform {
+    fn name(&self) -> &'static str {
+        "linux"
+    }
+
+    fn get_local_appdata(&self) -> Option<PathBuf> {
+        dirs::data_local_dir()
+    }
+
+    fn get_local_low(&self) -> Option<PathBuf> {
+        dirs::cache_dir()
+    }
+
+    fn get_roaming(&self) -> Option<PathBuf> {
+        dirs::config_dir()
+    }
+
+    fn get_appdata(&self) -> Option<PathBuf> {
+        dirs::data_dir()
+    }
+
+    fn open_explorer(&self, path: &Path) -> Result<(), JweError> {
+        let path = path
+            .canonicalize()
+            .map_err(|e| JweError::File(path.to_path_buf(), e))?;
+
+        match open::that(&path) {
+            Ok(_) => {
+                logger_control::log(
+                    &format!("Opened file manager at: {}", path.display()),
+                    logger_control::LogLevel::INFO,
+                );
+                Ok(())
+            }
+            Err(e) => {
+                logger_control::log(
+                    &format!("Failed to open file manager: {}", e),
+                    logger_control::LogLevel::CRITICAL,
+                );
+                Err(JweError::Platform(format!(
+                    "Failed to open file manager: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    fn open_task_manager(&self) -> Result<(), JweError> {
+        for program in TASK_MANAGERS {
+            if Command::new(program).spawn().is_ok() {
+                logger_control::log(
+                    &format!("Opened task manager: {}", program),
+                    logger_control::LogLevel::INFO,
+                );
+                return Ok(());
+            }
+        }
+
+        logger_control::log(
+            "Failed to open task manager: no system monitor found",
+            logger_control::LogLevel::CRITICAL,
+        );
+        Err(JweError::Platform("No system monitor found".to_string()))
+    }
+
+    fn open_environment_variables_window(&self) -> Result<(), JweError> {
+        logger_control::log(
+            "Failed to open environment variables window: Unsupported OS",
+            logger_control::LogLevel::CRITICAL,
+        );
+        Err(JweError::Platform(
+            "Environment variables window is not available on Linux".to_string(),
+        ))
+    }
+
+    fn refresh_explorer(&self) -> Result<(), JweError> {
+        logger_control::log(
+            "Failed to refresh explorer: Unsupported OS",
+            logger_control::LogLevel::CRITICAL,
+        );
+        Err(JweError::Platform(
+            "Explorer refresh is not available on Linux".to_string(),
+        ))
+    }
+
+    fn get_all_user_sids(&self) -> Result<Vec<String>, JweError> {
+        Err(JweError::Platform(
+            "SIDs are not available on Linux".to_string(),
+        ))
+    }
+
+    fn terminate_pid(&self, pid: u32) -> Result<(), JweError> {
+        send_signal(pid, Signal::Term)
+    }
+
+    fn kill_pid(&self, pid: u32) -> Result<(), JweError> {
+        send_signal(pid, Signal::Kill)
+    }
+
+    // sudo は環境変数を引き継がないので --preserve-env を付ける
+    fn elevated_command(&self, entry: &LauncherEntry) -> Result<Command, JweError> {
+        let mut command = Command::new("sudo");
+        command
+            .arg("--preserve-env")
+            .arg(&entry.program)
+            .args(&entry.args)
+            .envs(&entry.env);
+        if let Some(cwd) = &entry.cwd {
+            command.current_dir(cwd);
+        }
+        Ok(command)
+    }
+}
+
+fn send_signal(pid: u32, signal: Signal) -> Result<(), JweError> {
+    let pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+
+    match sys.process(pid).map(|process| process.kill_with(signal)) {
+        Some(Some(true)) => {
+            logger_control::log(
+                &format!("Sent {} to PID: {}", signal, pid),
+                logger_control::LogLevel::INFO,
+            );
+            Ok(())
+        }
+        Some(_) => {
+            logger_control::log(
+                &format!("Failed to send {} to PID: {}", signal, pid),
+                logger_control::LogLevel::CRITICAL,
+            );
+            Err(JweError::Platform(format!(
+                "Failed to send {} to PID {}",
+                signal, pid
+            )))
+        }
+        None => {
+            logger_control::log(
+                &format!("Failed to kill PID: {} not found", pid),
+                logger_control::LogLevel::CRITICAL,
+            );
+            Err(JweError::NotFound(format!("PID {} not found", pid)))
+        }
+    }
+}
@@ -1,11 +1,13 @@
-use std::{env::consts::OS, fs, fs::OpenOptions, io::Write, path::PathBuf};
+use std::{fs, fs::OpenOptions, io::Write, path::PathBuf};
 
 use once_cell::sync::OnceCell;
 
-use dirs;
+use super::error::JweError;
+use super::store;
 
 static LOG_TIME: OnceCell<PathBuf> = OnceCell::new();
 
+#[allow(dead_code, clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Copy)]
 pub enum LogLevel {
     INFO,
@@ -27,29 +29,21 @@ impl LogLevel {
     }
 }
 
-pub fn initialize() {
+// データディレクトリが使えない時はコマンドを何も実行せずにエラーで終わる
+pub fn initialize() -> Result<(), JweError> {
     let time = chrono::Local::now().format("%Y-%m-%d").to_string();
 
-    match OS {
-        "windows" => {
-            let local_app_data =
-                dirs::data_local_dir().expect("Failed to get local app data directory");
-            let log_dir = local_app_data.join("johma_windows_enhanced").join("logs");
-            if !log_dir.exists() {
-                fs::create_dir_all(&log_dir).expect("Failed to create log directory");
-            }
-
-            let log_file = log_dir.join(format!("{}.log", time));
-            LOG_TIME.set(log_file).unwrap();
-            if !LOG_TIME.get().unwrap().exists() {
-                fs::File::create(LOG_TIME.get().unwrap()).unwrap();
-            }
-        }
+    let log_dir = store::data_root()?.join("logs");
+    fs::create_dir_all(&log_dir).map_err(|e| JweError::File(log_dir.clone(), e))?;
 
-        _ => {
-            println!("logging not implemented for this OS");
-        }
-    }
+    let log_file = log_dir.join(format!("{}.log", time));
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_file)
+        .map_err(|e| JweError::File(log_file.clone(), e))?;
+    let _ = LOG_TIME.set(log_file);
+    Ok(())
 }
 
 pub fn log(message: &str, level: LogLevel) {
@@ -0,0 +1,92 @@
+use serde::Serialize;
+use sysinfo::System;
+
+use super::process_controller;
+use super::records::{MemoryRecord, ProcessRecord};
+use super::units;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PressureLevel {
+    Ok,
+    Warn,
+    Critical,
+}
+
+impl std::fmt::Display for PressureLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PressureLevel::Ok => write!(f, "ok"),
+            PressureLevel::Warn => write!(f, "warn"),
+            PressureLevel::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+pub struct Thresholds {
+    pub warn: f64,
+    pub critical: f64,
+}
+
+// キャッシュは必要になれば解放されるので、used ではなく available から足りなさを見る
+pub fn pressure_percent(sys: &System) -> f64 {
+    units::percent(
+        sys.total_memory().saturating_sub(sys.available_memory()),
+        sys.total_memory(),
+    )
+}
+
+pub fn pressure_level(percent: f64, thresholds: &Thresholds) -> PressureLevel {
+    if percent >= thresholds.critical {
+        PressureLevel::Critical
+    } else if percent >= thresholds.warn {
+        PressureLevel::Warn
+    } else {
+        PressureLevel::Ok
+    }
+}
+
+pub fn render(record: &MemoryRecord, processes: Option<&[ProcessRecord]>) -> String {
+    let mut lines = Vec::new();
+    if let Some(total_memory) = record.total {
+        lines.push(format!(
+            "Total Memory: {}",
+            units::format_bytes(total_memory)
+        ));
+    }
+    if let Some(free_memory) = record.free {
+        lines.push(format!("Free Memory: {}", units::format_bytes(free_memory)));
+    }
+    if let Some(used_memory) = record.used {
+        lines.push(format!("Used Memory: {}", units::format_bytes(used_memory)));
+    }
+    if let Some(available_memory) = record.available {
+        lines.push(format!(
+            "Available Memory: {}",
+            units::format_bytes(available_memory)
+        ));
+    }
+    if let Some(used_percent) = record.used_percent {
+        lines.push(format!("Used Memory Percent: {:.1}%", used_percent));
+    }
+    if let (Some(swap_total), Some(swap_used), Some(swap_free)) =
+        (record.swap_total, record.swap_used, record.swap_free)
+    {
+        lines.push(format!("Total Swap: {}", units::format_bytes(swap_total)));
+        lines.push(format!("Used Swap: {}", units::format_bytes(swap_used)));
+        lines.push(format!("Free Swap: {}", units::format_bytes(swap_free)));
+    }
+    if let Some(swap_used_percent) = record.swap_used_percent {
+        lines.push(format!("Used Swap Percent: {:.1}%", swap_used_percent));
+    }
+    if let (Some(level), Some(pressure_percent)) = (record.pressure, record.pressure_percent) {
+        lines.push(format!(
+            "Memory Pressure: {} ({:.1}% in use)",
+            level, pressure_percent
+        ));
+    }
+    if let Some(processes) = processes {
+        lines.push(process_controller::table(processes));
+    }
+    lines.join("\n")
+}
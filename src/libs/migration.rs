@@ -0,0 +1,225 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use toml::{Table, Value};
+
+use crate::VERISON;
+
+use super::data_controller::{self, Settings};
+use super::error::JweError;
+use super::logger_control;
+
+struct Migration {
+    version: &'static str,
+    description: &'static str,
+    apply: fn(&mut Table),
+}
+
+// 古い順に並べる。保存されているバージョンより新しく、CARGO_PKG_VERSION 以下のものが順番に適用される
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: "0.1.0",
+    description: "Fill empty browser and web_search",
+    apply: fill_empty_fields,
+}];
+
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    pub name: &'static str,
+    pub from: String,
+    pub to: String,
+    pub applied: Vec<&'static str>,
+    pub changes: Vec<String>,
+    pub backup: Option<PathBuf>,
+}
+
+impl MigrationReport {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+// "1.2.3" -> (1, 2, 3)。読めない部分は 0 として扱う
+pub fn parse_version(version: &str) -> (u64, u64, u64) {
+    let mut parts = version
+        .trim()
+        .trim_start_matches('v')
+        .split(['.', '-', '+'])
+        .map(|part| part.parse::<u64>().unwrap_or(0));
+
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
+
+// settings.toml.0.1.0.20240101120000.bak のように時刻を付けて、前のバックアップを上書きしない
+fn backup(path: &Path, label: &str) -> Result<PathBuf, JweError> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let stamp = Local::now().format("%Y%m%d%H%M%S").to_string();
+    // 同じ秒に 2 回移行した時は連番を付ける
+    let backup = (1..)
+        .map(|n| {
+            let suffix = if n == 1 {
+                String::new()
+            } else {
+                format!("-{}", n)
+            };
+            path.with_file_name(format!("{}.{}.{}{}.bak", file_name, label, stamp, suffix))
+        })
+        .find(|backup| !backup.exists())
+        .unwrap_or_default();
+
+    fs::copy(path, &backup).map_err(|e| JweError::File(backup.clone(), e))?;
+    Ok(backup)
+}
+
+pub fn migrate_settings() -> Result<MigrationReport, JweError> {
+    let raw_store = data_controller::raw_settings_store()?;
+    let original = raw_store.read()?;
+
+    let from = original
+        .get("version")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let from_version = parse_version(&from);
+    let to_version = parse_version(VERISON);
+
+    let mut report = MigrationReport {
+        name: "Settings",
+        from: from.clone(),
+        to: VERISON.to_string(),
+        ..Default::default()
+    };
+
+    if from_version > to_version {
+        logger_control::log(
+            &format!(
+                "Settings version {} is newer than {}, skip migration",
+                from, VERISON
+            ),
+            logger_control::LogLevel::WARNING,
+        );
+        return Ok(report);
+    }
+
+    let mut table = original.clone();
+    for migration in MIGRATIONS {
+        let version = parse_version(migration.version);
+        if version > from_version && version <= to_version {
+            (migration.apply)(&mut table);
+            report.applied.push(migration.description);
+        }
+    }
+
+    let mut settings: Settings = Value::Table(table)
+        .try_into()
+        .map_err(|e| JweError::Parse(format!("Failed to migrate settings: {}", e)))?;
+    settings.version = VERISON.to_string();
+
+    let migrated = match Value::try_from(&settings) {
+        Ok(Value::Table(migrated)) => migrated,
+        _ => return Err(JweError::Parse("Failed to serialize settings".to_string())),
+    };
+
+    for (key, value) in &migrated {
+        match original.get(key) {
+            None => report.changes.push(format!("added {} = {}", key, value)),
+            Some(old) if old != value => report
+                .changes
+                .push(format!("{}: {} -> {}", key, old, value)),
+            _ => {}
+        }
+    }
+    for key in original.keys() {
+        if !migrated.contains_key(key) {
+            report.changes.push(format!("removed unknown key {}", key));
+        }
+    }
+
+    if report.is_empty() {
+        return Ok(report);
+    }
+
+    let backup = backup(
+        raw_store.path(),
+        if from.is_empty() { "unknown" } else { &from },
+    )?;
+    report.backup = Some(backup);
+
+    data_controller::write_settings(settings)?;
+
+    for change in &report.changes {
+        logger_control::log(
+            &format!("Settings migration {}", change),
+            logger_control::LogLevel::INFO,
+        );
+    }
+
+    Ok(report)
+}
+
+// 以前の launcher.toml は name = "path" の形式だったので { program = "path" } に変換する
+pub fn migrate_launchers() -> Result<MigrationReport, JweError> {
+    let raw_store = data_controller::raw_launcher_store()?;
+    let mut report = MigrationReport {
+        name: "Launchers",
+        from: "name = path".to_string(),
+        to: "launcher entries".to_string(),
+        ..Default::default()
+    };
+
+    if !raw_store.exists() {
+        return Ok(report);
+    }
+
+    let mut table = raw_store.read()?;
+    if let Some(Value::Table(launchers)) = table.get_mut("launchers") {
+        for (name, value) in launchers.iter_mut() {
+            if let Value::String(path) = value {
+                report
+                    .changes
+                    .push(format!("{}: {} -> program = {}", name, path, path));
+
+                let mut entry = Table::new();
+                entry.insert("program".to_string(), Value::String(path.clone()));
+                *value = Value::Table(entry);
+            }
+        }
+    }
+
+    if report.is_empty() {
+        return Ok(report);
+    }
+
+    report.backup = Some(backup(raw_store.path(), "launchers")?);
+
+    raw_store.write(&table)?;
+    report
+        .applied
+        .push("Convert launcher paths to launcher entries");
+
+    for change in &report.changes {
+        logger_control::log(
+            &format!("Launcher migration {}", change),
+            logger_control::LogLevel::INFO,
+        );
+    }
+
+    Ok(report)
+}
+
+fn fill_empty_fields(table: &mut Table) {
+    let defaults = Settings::default();
+
+    for (key, default) in [
+        ("browser", defaults.browser),
+        ("web_search", defaults.web_search),
+    ] {
+        if table.get(key).and_then(Value::as_str) == Some("") {
+            table.insert(key.to_string(), Value::String(default));
+        }
+    }
+}
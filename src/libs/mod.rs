@@ -1,4 +1,30 @@
 pub mod browser_controller;
+pub mod config_controller;
 pub mod data_controller;
+pub mod disk_controller;
+pub mod error;
+pub mod file_controller;
+pub mod find;
+pub mod launcher;
+#[cfg(not(target_os = "windows"))]
+pub mod linux_api;
 pub mod logger_control;
+pub mod memory_controller;
+pub mod migration;
+pub mod monitor;
+pub mod network_controller;
+pub mod output;
+pub mod platform;
+pub mod process_controller;
+pub mod prompt;
+pub mod records;
+pub mod sensors_controller;
+pub mod store;
+pub mod system_controller;
+pub mod top;
+pub mod trash;
+pub mod tree;
+pub mod units;
+pub mod watch;
+#[cfg(target_os = "windows")]
 pub mod win_api;
@@ -0,0 +1,401 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use sysinfo::{Disks, Networks, System, Users, MINIMUM_CPU_UPDATE_INTERVAL};
+use tabled::{builder::Builder, settings::Style};
+
+use super::error::JweError;
+use super::logger_control;
+use super::output::{self, OutputFormat};
+use super::process_controller::{self, ProcessSort};
+use super::records::{MonitorReport, ProcessUsageRecord};
+use super::store;
+use super::units;
+use super::watch::{self, Stats, WatchOptions};
+
+const HISTORY_DIR: &str = "monitor";
+const HISTORY_FILE: &str = "history.jsonl";
+// 1 回のサンプルで CPU とメモリそれぞれ上位何件のプロセスを残すか
+const TOP_PROCESSES: usize = 5;
+// 記録中に古いサンプルを消す間隔
+const TRIM_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SampleProcess {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_usage: f32,
+    pub memory: u64,
+}
+
+// history.jsonl の 1 行。ネットワークは前回のサンプルからの差分
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MonitorSample {
+    pub timestamp: i64,
+    pub interval_secs: f64,
+    pub cpu_usage: f32,
+    pub memory_used: u64,
+    pub memory_total: u64,
+    pub disk_used: u64,
+    pub disk_total: u64,
+    pub net_received: u64,
+    pub net_transmitted: u64,
+    #[serde(default)]
+    pub processes: Vec<SampleProcess>,
+}
+
+pub fn history_path() -> Result<PathBuf, JweError> {
+    Ok(store::data_root()?.join(HISTORY_DIR).join(HISTORY_FILE))
+}
+
+struct Sampler {
+    sys: System,
+    disks: Disks,
+    networks: Networks,
+    users: Users,
+    last: Instant,
+}
+
+impl Sampler {
+    fn new() -> Self {
+        let mut sys = System::new();
+        sys.refresh_cpu_usage();
+        process_controller::refresh(&mut sys);
+        let sampler = Sampler {
+            sys,
+            disks: Disks::new_with_refreshed_list(),
+            networks: Networks::new_with_refreshed_list(),
+            users: Users::new(),
+            last: Instant::now(),
+        };
+        std::thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+        sampler
+    }
+
+    fn sample(&mut self) -> MonitorSample {
+        self.sys.refresh_cpu_usage();
+        self.sys.refresh_memory();
+        process_controller::refresh(&mut self.sys);
+        self.disks.refresh();
+        self.networks.refresh();
+
+        let interval_secs = self.last.elapsed().as_secs_f64();
+        self.last = Instant::now();
+
+        // 同じディスクが複数のマウントポイントに出てくることがあるので名前で重複を除く
+        let mut seen = HashSet::new();
+        let (disk_used, disk_total) = self
+            .disks
+            .list()
+            .iter()
+            .filter(|disk| seen.insert(disk.name().to_os_string()))
+            .fold((0, 0), |(used, total), disk| {
+                (
+                    used + disk.total_space().saturating_sub(disk.available_space()),
+                    total + disk.total_space(),
+                )
+            });
+
+        MonitorSample {
+            timestamp: Local::now().timestamp(),
+            interval_secs,
+            cpu_usage: self.sys.global_cpu_usage(),
+            memory_used: self.sys.used_memory(),
+            memory_total: self.sys.total_memory(),
+            disk_used,
+            disk_total,
+            net_received: self
+                .networks
+                .list()
+                .values()
+                .map(|data| data.received())
+                .sum(),
+            net_transmitted: self
+                .networks
+                .list()
+                .values()
+                .map(|data| data.transmitted())
+                .sum(),
+            processes: self.top_processes(),
+        }
+    }
+
+    fn top_processes(&self) -> Vec<SampleProcess> {
+        // 記録している jwe 自身は除く
+        let mut records = process_controller::records(&self.sys, &self.users);
+        records.retain(|record| record.pid != std::process::id());
+        let mut processes: Vec<SampleProcess> = Vec::new();
+
+        for sort in [ProcessSort::Cpu, ProcessSort::Mem] {
+            process_controller::sort_records(&mut records, sort);
+            for record in records.iter().take(TOP_PROCESSES) {
+                if !processes.iter().any(|process| process.pid == record.pid) {
+                    processes.push(SampleProcess {
+                        pid: record.pid,
+                        name: record.name.clone(),
+                        cpu_usage: record.cpu_usage,
+                        memory: record.memory,
+                    });
+                }
+            }
+        }
+        processes
+    }
+}
+
+fn append(path: &PathBuf, sample: &MonitorSample) -> Result<(), JweError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| JweError::File(dir.to_path_buf(), e))?;
+    }
+    let line = serde_json::to_string(sample)
+        .map_err(|e| JweError::Parse(format!("Failed to serialize sample: {}", e)))?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| JweError::File(path.clone(), e))?;
+    writeln!(file, "{}", line).map_err(|e| JweError::File(path.clone(), e))
+}
+
+#[derive(Deserialize)]
+struct SampleTime {
+    timestamp: i64,
+}
+
+// keep より古いサンプルを消す。消すものが無ければ書き直さない
+fn trim(path: &PathBuf, keep: Duration) -> Result<(), JweError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(JweError::File(path.clone(), e)),
+    };
+
+    let from = Local::now().timestamp() - keep.as_secs() as i64;
+    let lines: Vec<&str> = content.lines().collect();
+    // 読めない行は report と同じく飛ばすだけなので残しておく
+    let kept: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|line| {
+            serde_json::from_str::<SampleTime>(line)
+                .map(|sample| sample.timestamp >= from)
+                .unwrap_or(true)
+        })
+        .collect();
+    if kept.len() == lines.len() {
+        return Ok(());
+    }
+
+    let mut trimmed = kept.join("\n");
+    if !trimmed.is_empty() {
+        trimmed.push('\n');
+    }
+    let tmp = path.with_extension("jsonl.tmp");
+    fs::write(&tmp, trimmed).map_err(|e| JweError::File(tmp.clone(), e))?;
+    fs::rename(&tmp, path).map_err(|e| JweError::File(path.clone(), e))?;
+
+    logger_control::log(
+        &format!(
+            "Monitor history trimmed {} samples older than {}s",
+            lines.len() - kept.len(),
+            keep.as_secs()
+        ),
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
+
+fn format_time(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+// keep より古いサンプルは始めと、その後は 1 時間ごとに消す
+pub fn record(options: &WatchOptions, keep: Duration) -> Result<(), JweError> {
+    let path = history_path()?;
+    let mut sampler = Sampler::new();
+    println!("Recording to {} (Ctrl+C to stop)", path.display());
+
+    trim(&path, keep)?;
+    let mut last_trim = Instant::now();
+    let samples = watch::run(options, |_| {
+        if last_trim.elapsed() >= TRIM_INTERVAL {
+            trim(&path, keep)?;
+            last_trim = Instant::now();
+        }
+        let sample = sampler.sample();
+        append(&path, &sample)?;
+        println!(
+            "{}  cpu {:5.1}%  mem {}  disk {}  net rx {} tx {}",
+            format_time(sample.timestamp),
+            sample.cpu_usage,
+            units::format_bytes(sample.memory_used),
+            units::format_bytes(sample.disk_used),
+            units::format_rate(sample.net_received as f64 / sample.interval_secs),
+            units::format_rate(sample.net_transmitted as f64 / sample.interval_secs)
+        );
+        Ok(())
+    })?;
+
+    logger_control::log(
+        &format!("Monitor recorded {} samples to {}", samples, path.display()),
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
+
+fn read_history(since: Duration) -> Result<Vec<MonitorSample>, JweError> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Err(JweError::NotFound(format!(
+            "No monitor history at {} (run jwe monitor record first)",
+            path.display()
+        )));
+    }
+
+    let from = Local::now().timestamp() - since.as_secs() as i64;
+    let file = fs::File::open(&path).map_err(|e| JweError::File(path.clone(), e))?;
+    let mut samples = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| JweError::File(path.clone(), e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // 書き込み途中で止まった行は読み飛ばす
+        match serde_json::from_str::<MonitorSample>(&line) {
+            Ok(sample) if sample.timestamp >= from => samples.push(sample),
+            Ok(_) => {}
+            Err(e) => logger_control::log(
+                &format!("Skipped monitor history line {}: {}", index + 1, e),
+                logger_control::LogLevel::WARNING,
+            ),
+        }
+    }
+    Ok(samples)
+}
+
+fn summarize(samples: &[MonitorSample], top: usize) -> MonitorReport {
+    let mut cpu = Stats::default();
+    let mut memory = Stats::default();
+    let mut memory_percent = Stats::default();
+    let mut disk = Stats::default();
+    let mut received = Stats::default();
+    let mut transmitted = Stats::default();
+    let mut processes: HashMap<String, (Stats, u64)> = HashMap::new();
+
+    for sample in samples {
+        cpu.add(sample.cpu_usage as f64);
+        memory.add(sample.memory_used as f64);
+        if sample.memory_total > 0 {
+            memory_percent.add(units::percent(sample.memory_used, sample.memory_total));
+        }
+        disk.add(sample.disk_used as f64);
+        if sample.interval_secs > 0.0 {
+            received.add(sample.net_received as f64 / sample.interval_secs);
+            transmitted.add(sample.net_transmitted as f64 / sample.interval_secs);
+        }
+        for process in &sample.processes {
+            let (stats, peak_memory) = processes.entry(process.name.clone()).or_default();
+            stats.add(process.cpu_usage as f64);
+            *peak_memory = (*peak_memory).max(process.memory);
+        }
+    }
+
+    // CPU の合計が大きい順 (よく上位に出てきて、かつ重いもの)
+    let mut processes: Vec<ProcessUsageRecord> = processes
+        .into_iter()
+        .map(|(name, (stats, peak_memory))| ProcessUsageRecord {
+            name,
+            samples: stats.count(),
+            avg_cpu: stats.avg(),
+            peak_cpu: stats.max(),
+            peak_memory,
+        })
+        .collect();
+    processes.sort_by(|a, b| {
+        (b.avg_cpu * b.samples as f64)
+            .total_cmp(&(a.avg_cpu * a.samples as f64))
+            .then(b.peak_memory.cmp(&a.peak_memory))
+    });
+    processes.truncate(top);
+
+    MonitorReport {
+        from: samples.first().map(|sample| format_time(sample.timestamp)),
+        to: samples.last().map(|sample| format_time(sample.timestamp)),
+        samples: samples.len(),
+        metrics: vec![
+            cpu.summary("cpu", "%"),
+            memory.summary("memory_used", "bytes"),
+            memory_percent.summary("memory_used_percent", "%"),
+            disk.summary("disk_used", "bytes"),
+            received.summary("net_received", "bytes/s"),
+            transmitted.summary("net_transmitted", "bytes/s"),
+        ],
+        processes,
+    }
+}
+
+fn render(report: &MonitorReport) -> String {
+    let mut builder = Builder::default();
+    builder.push_record(["Metric", "Min", "Avg", "Peak", "Samples"]);
+    for metric in &report.metrics {
+        builder.push_record([
+            metric.metric.clone(),
+            watch::format_value(metric.min, &metric.unit),
+            watch::format_value(metric.avg, &metric.unit),
+            watch::format_value(metric.max, &metric.unit),
+            metric.samples.to_string(),
+        ]);
+    }
+    let mut metrics = builder.build();
+    metrics.with(Style::ascii_rounded());
+
+    let mut builder = Builder::default();
+    builder.push_record(["Process", "Seen", "Avg CPU %", "Peak CPU %", "Peak memory"]);
+    for process in &report.processes {
+        builder.push_record([
+            process.name.clone(),
+            process.samples.to_string(),
+            format!("{:.1}", process.avg_cpu),
+            format!("{:.1}", process.peak_cpu),
+            units::format_bytes(process.peak_memory),
+        ]);
+    }
+    let mut processes = builder.build();
+    processes.with(Style::ascii_rounded());
+
+    format!(
+        "{} samples from {} to {}\n{}\nTop processes\n{}",
+        report.samples,
+        report.from.as_deref().unwrap_or("-"),
+        report.to.as_deref().unwrap_or("-"),
+        metrics,
+        processes
+    )
+}
+
+pub fn report(format: OutputFormat, since: Duration, top: usize) -> Result<(), JweError> {
+    let samples = read_history(since)?;
+    let report = summarize(&samples, top);
+
+    // CSV は入れ子にできないので指標だけを出す
+    if format == OutputFormat::Csv {
+        output::print(format, &report.metrics, String::new)?;
+    } else {
+        output::print_one(format, &report, || render(&report))?;
+    }
+    logger_control::log(
+        &format!("Monitor report called for {} samples", report.samples),
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use sysinfo::Networks;
+use tabled::{builder::Builder, settings::Style};
+
+use super::error::JweError;
+use super::logger_control;
+use super::output::OutputFormat;
+use super::records::NetworkRecord;
+use super::units;
+use super::watch::{self, Stats, WatchOptions};
+
+pub fn list() -> Vec<NetworkRecord> {
+    let networks = Networks::new_with_refreshed_list();
+    let mut records: Vec<NetworkRecord> = networks
+        .list()
+        .iter()
+        .map(|(name, data)| NetworkRecord {
+            interface: name.clone(),
+            mac: data.mac_address().to_string(),
+            ips: data
+                .ip_networks()
+                .iter()
+                .map(|ip| ip.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            received: data.total_received(),
+            transmitted: data.total_transmitted(),
+            packets_received: data.total_packets_received(),
+            packets_transmitted: data.total_packets_transmitted(),
+            errors_received: data.total_errors_on_received(),
+            errors_transmitted: data.total_errors_on_transmitted(),
+        })
+        .collect();
+    records.sort_by(|a, b| a.interface.cmp(&b.interface));
+    records
+}
+
+pub fn table(records: &[NetworkRecord]) -> String {
+    let mut builder = Builder::default();
+    builder.push_record([
+        "Interface",
+        "MAC",
+        "IP",
+        "Received",
+        "Transmitted",
+        "Packets in/out",
+        "Errors in/out",
+    ]);
+    for record in records {
+        builder.push_record([
+            record.interface.clone(),
+            record.mac.clone(),
+            record.ips.clone(),
+            units::format_bytes(record.received),
+            units::format_bytes(record.transmitted),
+            format!("{}/{}", record.packets_received, record.packets_transmitted),
+            format!("{}/{}", record.errors_received, record.errors_transmitted),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii_rounded());
+    table.to_string()
+}
+
+// 1 秒あたりの送受信量をインターフェースごとに出す
+pub fn watch(
+    format: OutputFormat,
+    options: &WatchOptions,
+    interface: Option<&str>,
+) -> Result<(), JweError> {
+    let mut networks = Networks::new_with_refreshed_list();
+    if let Some(interface) = interface {
+        if !networks.list().contains_key(interface) {
+            return Err(JweError::NotFound(format!(
+                "Network interface not found {}",
+                interface
+            )));
+        }
+    }
+
+    let mut rates: BTreeMap<String, (Stats, Stats)> = BTreeMap::new();
+    let mut last = Instant::now();
+
+    let samples = watch::run(options, |_| {
+        // 最初のサンプルも 1 間隔分の差分になるように待つ
+        if last.elapsed() < options.interval {
+            watch::sleep(options.interval - last.elapsed());
+            if watch::stopped() {
+                return Ok(());
+            }
+        }
+        networks.refresh();
+        let secs = last.elapsed().as_secs_f64();
+        last = Instant::now();
+
+        let mut names: Vec<&String> = networks
+            .list()
+            .keys()
+            .filter(|name| interface.is_none_or(|interface| interface == name.as_str()))
+            .collect();
+        names.sort();
+
+        for name in names {
+            let data = &networks.list()[name];
+            let received = data.received() as f64 / secs;
+            let transmitted = data.transmitted() as f64 / secs;
+            let (received_stats, transmitted_stats) = rates.entry(name.clone()).or_default();
+            received_stats.add(received);
+            transmitted_stats.add(transmitted);
+
+            watch::emit(
+                format,
+                format!(
+                    "{}  {:<16} rx {:>12}  tx {:>12}",
+                    watch::timestamp(),
+                    name,
+                    units::format_rate(received),
+                    units::format_rate(transmitted)
+                ),
+            );
+        }
+        Ok(())
+    })?;
+
+    let summary: Vec<_> = rates
+        .iter()
+        .flat_map(|(name, (received, transmitted))| {
+            [
+                received.summary(&format!("{} rx", name), "bytes/s"),
+                transmitted.summary(&format!("{} tx", name), "bytes/s"),
+            ]
+        })
+        .collect();
+    watch::print_summary(format, &summary)?;
+    logger_control::log(
+        &format!("Net watch finished after {} samples", samples),
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::env;
+use std::io::{self, IsTerminal};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use tabled::{
+    builder::Builder,
+    settings::{
+        object::{Cell, Columns, Rows},
+        Alignment, Color, Modify, Padding, Style, Width,
+    },
+};
+
+use super::error::JweError;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Yaml,
+    Csv,
+}
+
+fn serialize_error(e: impl std::fmt::Display) -> JweError {
+    JweError::Parse(format!("Failed to serialize output: {}", e))
+}
+
+// table の時は呼び出し側の表示をそのまま使い、それ以外は records をシリアライズする
+pub fn print<T, F>(format: OutputFormat, records: &[T], table: F) -> Result<(), JweError>
+where
+    T: Serialize,
+    F: FnOnce() -> String,
+{
+    match format {
+        OutputFormat::Table => println!("{}", table()),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(records).map_err(serialize_error)?
+        ),
+        OutputFormat::Yaml => print!(
+            "{}",
+            serde_yaml::to_string(records).map_err(serialize_error)?
+        ),
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(io::stdout());
+            for record in records {
+                writer.serialize(record).map_err(serialize_error)?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+// 1 件だけの結果用
+pub fn print_one<T, F>(format: OutputFormat, record: &T, table: F) -> Result<(), JweError>
+where
+    T: Serialize,
+    F: FnOnce() -> String,
+{
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(record).map_err(serialize_error)?
+            );
+            Ok(())
+        }
+        OutputFormat::Yaml => {
+            print!(
+                "{}",
+                serde_yaml::to_string(record).map_err(serialize_error)?
+            );
+            Ok(())
+        }
+        _ => print(format, std::slice::from_ref(record), table),
+    }
+}
+
+// 端末に出す時だけ色を付ける。NO_COLOR が設定されていれば付けない
+pub fn use_color() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+// ls や fav list で使っている 3 列のグリッド表示
+pub fn grid(items: &[String]) -> String {
+    colored_grid(items, &[])
+}
+
+// colors[i] が items[i] の色。足りない分は色を付けない
+pub fn colored_grid(items: &[String], colors: &[Option<Color>]) -> String {
+    const ITEMS_PER_ROW: usize = 3;
+    let mut builder = Builder::default();
+
+    for chunk in items.chunks(ITEMS_PER_ROW) {
+        builder.push_record(chunk.to_vec());
+    }
+    let mut table = builder.build();
+    table
+        .with(Style::ascii_rounded())
+        .with(Padding::new(1, 1, 0, 0))
+        .with(Alignment::left())
+        .with(Modify::new(Columns::new(..)).with(Width::wrap(30)))
+        .with(Modify::new(Rows::new(..)).with(Padding::new(0, 1, 0, 0)));
+    for (index, color) in colors.iter().enumerate() {
+        if let Some(color) = color {
+            table.modify(
+                Cell::new(index / ITEMS_PER_ROW, index % ITEMS_PER_ROW),
+                color.clone(),
+            );
+        }
+    }
+    table.to_string()
+}
@@ -0,0 +1,59 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::data_controller::LauncherEntry;
+use super::error::JweError;
+use super::launcher;
+use super::process_controller;
+use super::records::ProcessRecord;
+use super::store;
+
+#[cfg(not(target_os = "windows"))]
+pub use super::linux_api::LinuxPlatform as CurrentPlatform;
+#[cfg(target_os = "windows")]
+pub use super::win_api::WindowsPlatform as CurrentPlatform;
+
+// OS ごとの処理はこの trait を通して呼ぶ
+pub trait Platform {
+    fn name(&self) -> &'static str;
+
+    fn get_local_appdata(&self) -> Option<PathBuf>;
+    fn get_local_low(&self) -> Option<PathBuf>;
+    fn get_roaming(&self) -> Option<PathBuf>;
+    fn get_appdata(&self) -> Option<PathBuf>;
+
+    fn get_app_folder(&self) -> Option<PathBuf> {
+        store::data_root().ok().map(Path::to_path_buf)
+    }
+
+    fn open_explorer(&self, path: &Path) -> Result<(), JweError>;
+    fn open_task_manager(&self) -> Result<(), JweError>;
+    fn open_environment_variables_window(&self) -> Result<(), JweError>;
+    fn refresh_explorer(&self) -> Result<(), JweError>;
+
+    fn get_all_user_sids(&self) -> Result<Vec<String>, JweError>;
+
+    fn list_processes(&self) -> Result<Vec<ProcessRecord>, JweError> {
+        Ok(process_controller::snapshot())
+    }
+
+    // 終了を依頼するだけで、終わったかどうかは呼び出し側で確認する
+    fn terminate_pid(&self, pid: u32) -> Result<(), JweError>;
+    fn kill_pid(&self, pid: u32) -> Result<(), JweError>;
+
+    // 管理者権限で起動するコマンドを作る
+    fn elevated_command(&self, entry: &LauncherEntry) -> Result<Command, JweError>;
+
+    fn run_launcher(&self, name: &str, entry: &LauncherEntry) -> Result<(), JweError> {
+        let command = if entry.elevated {
+            self.elevated_command(entry)?
+        } else {
+            launcher::build_command(entry)
+        };
+        launcher::execute(name, entry, command)
+    }
+}
+
+pub fn current() -> CurrentPlatform {
+    CurrentPlatform
+}
@@ -0,0 +1,399 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, TimeZone};
+use clap::ValueEnum;
+use glob::{MatchOptions, Pattern};
+use regex::{Regex, RegexBuilder};
+use sysinfo::{
+    Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System, ThreadKind, Users,
+    MINIMUM_CPU_UPDATE_INTERVAL,
+};
+use tabled::{builder::Builder, settings::Style};
+
+use super::error::JweError;
+use super::platform::Platform;
+use super::records::{KillRecord, ProcessRecord};
+use super::units;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ProcessSort {
+    Cpu,
+    Mem,
+    #[default]
+    Pid,
+}
+
+#[derive(Debug, Default)]
+pub struct ProcessFilter {
+    pub name: Option<String>,
+    pub user: Option<String>,
+}
+
+impl ProcessFilter {
+    fn matches(&self, record: &ProcessRecord) -> bool {
+        let name_matches = self
+            .name
+            .as_ref()
+            .is_none_or(|name| record.name.to_lowercase().contains(&name.to_lowercase()));
+        let user_matches = self.user.as_ref().is_none_or(|user| {
+            record
+                .user
+                .as_ref()
+                .is_some_and(|record_user| record_user.eq_ignore_ascii_case(user))
+        });
+        name_matches && user_matches
+    }
+}
+
+// CPU 使用率は 2 回の更新の差分で計算されるので、間を空けてもう一度更新する
+pub fn snapshot() -> Vec<ProcessRecord> {
+    let mut sys = System::new();
+    refresh(&mut sys);
+    thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+    refresh(&mut sys);
+
+    let users = Users::new_with_refreshed_list();
+    records(&sys, &users)
+}
+
+pub fn refresh(sys: &mut System) {
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::everything(),
+    );
+}
+
+// Linux ではユーザースレッドもプロセスとして見えるので除外する
+pub fn records(sys: &System, users: &Users) -> Vec<ProcessRecord> {
+    sys.processes()
+        .iter()
+        .filter(|(_, process)| process.thread_kind() != Some(ThreadKind::Userland))
+        .map(|(pid, process)| ProcessRecord {
+            pid: pid.as_u32(),
+            parent_pid: process.parent().map(|parent| parent.as_u32()),
+            name: process.name().to_string_lossy().to_string(),
+            exe: process.exe().map(|exe| exe.display().to_string()),
+            cpu_usage: process.cpu_usage(),
+            memory: process.memory(),
+            user: process
+                .user_id()
+                .and_then(|uid| users.get_user_by_id(uid))
+                .map(|user| user.name().to_string()),
+            start_time: Local
+                .timestamp_opt(process.start_time() as i64, 0)
+                .single()
+                .filter(|_| process.start_time() > 0)
+                .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string()),
+        })
+        .collect()
+}
+
+// フィルター、並び替え、上位 N 件の順に適用する
+pub fn select(
+    mut records: Vec<ProcessRecord>,
+    filter: &ProcessFilter,
+    sort: ProcessSort,
+    top: Option<usize>,
+) -> Vec<ProcessRecord> {
+    records.retain(|record| filter.matches(record));
+    sort_records(&mut records, sort);
+    if let Some(top) = top {
+        records.truncate(top);
+    }
+    records
+}
+
+pub fn sort_records(records: &mut [ProcessRecord], sort: ProcessSort) {
+    match sort {
+        ProcessSort::Cpu => {
+            records.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage).then(a.pid.cmp(&b.pid)))
+        }
+        ProcessSort::Mem => records.sort_by(|a, b| b.memory.cmp(&a.memory).then(a.pid.cmp(&b.pid))),
+        ProcessSort::Pid => records.sort_by_key(|record| record.pid),
+    }
+}
+
+pub fn table(records: &[ProcessRecord]) -> String {
+    let mut builder = Builder::default();
+    builder.push_record([
+        "PID", "PPID", "Name", "CPU %", "Memory", "User", "Started", "Path",
+    ]);
+    for record in records {
+        builder.push_record([
+            record.pid.to_string(),
+            record
+                .parent_pid
+                .map(|pid| pid.to_string())
+                .unwrap_or_default(),
+            record.name.clone(),
+            format!("{:.1}", record.cpu_usage),
+            units::format_bytes(record.memory),
+            record.user.clone().unwrap_or_default(),
+            record.start_time.clone().unwrap_or_default(),
+            record.exe.clone().unwrap_or_default(),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii_rounded());
+    table.to_string()
+}
+
+// 親プロセスが一覧に無いものをルートにして、records の並び順のまま子を並べる
+pub fn tree(records: &[ProcessRecord]) -> String {
+    let pids: HashSet<u32> = records.iter().map(|record| record.pid).collect();
+    let mut children: HashMap<u32, Vec<&ProcessRecord>> = HashMap::new();
+    let mut roots = Vec::new();
+
+    for record in records {
+        match record.parent_pid {
+            Some(parent) if parent != record.pid && pids.contains(&parent) => {
+                children.entry(parent).or_default().push(record)
+            }
+            _ => roots.push(record),
+        }
+    }
+
+    let mut builder = Builder::default();
+    builder.push_record(["Process", "PID", "CPU %", "Memory", "User"]);
+
+    let mut visited = HashSet::new();
+    let mut stack: Vec<(&ProcessRecord, String, bool, bool)> = roots
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, record)| (*record, String::new(), index == 0, true))
+        .collect();
+
+    while let Some((record, prefix, last, root)) = stack.pop() {
+        if !visited.insert(record.pid) {
+            continue;
+        }
+
+        let (branch, child_prefix) = match (root, last) {
+            (true, _) => (String::new(), String::new()),
+            (false, true) => (format!("{}└─ ", prefix), format!("{}   ", prefix)),
+            (false, false) => (format!("{}├─ ", prefix), format!("{}│  ", prefix)),
+        };
+        builder.push_record([
+            format!("{}{}", branch, record.name),
+            record.pid.to_string(),
+            format!("{:.1}", record.cpu_usage),
+            units::format_bytes(record.memory),
+            record.user.clone().unwrap_or_default(),
+        ]);
+
+        if let Some(items) = children.get(&record.pid) {
+            for (index, child) in items.iter().rev().enumerate() {
+                stack.push((child, child_prefix.clone(), index == 0, false));
+            }
+        }
+    }
+
+    let mut table = builder.build();
+    table.with(Style::ascii_rounded());
+    table.to_string()
+}
+
+pub enum ProcessTarget {
+    Pid(u32),
+    Name(String),
+    Glob(Pattern),
+    Regex(Regex),
+}
+
+// 数字なら PID、* ? [ を含めば glob、それ以外は名前の完全一致 (大文字小文字は区別しない)
+pub fn parse_target(target: &str, regex: bool) -> Result<ProcessTarget, JweError> {
+    let target = target.trim();
+    if target.is_empty() {
+        return Err(JweError::InvalidInput(
+            "Process name must not be empty".to_string(),
+        ));
+    }
+
+    if regex {
+        return RegexBuilder::new(target)
+            .case_insensitive(true)
+            .build()
+            .map(ProcessTarget::Regex)
+            .map_err(|e| JweError::InvalidInput(format!("Invalid regex {}: {}", target, e)));
+    }
+    if let Ok(pid) = target.parse::<u32>() {
+        return Ok(ProcessTarget::Pid(pid));
+    }
+    if target.contains(['*', '?', '[']) {
+        return Pattern::new(target)
+            .map(ProcessTarget::Glob)
+            .map_err(|e| JweError::InvalidInput(format!("Invalid pattern {}: {}", target, e)));
+    }
+    Ok(ProcessTarget::Name(target.to_lowercase()))
+}
+
+impl ProcessTarget {
+    pub fn matches(&self, record: &ProcessRecord) -> bool {
+        match self {
+            ProcessTarget::Pid(pid) => record.pid == *pid,
+            // notepad でも notepad.exe でも当たるようにする
+            ProcessTarget::Name(name) => {
+                let record_name = record.name.to_lowercase();
+                let stem = Path::new(&record_name)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                record_name == *name || stem == *name
+            }
+            ProcessTarget::Glob(pattern) => pattern.matches_with(
+                &record.name,
+                MatchOptions {
+                    case_sensitive: false,
+                    ..MatchOptions::new()
+                },
+            ),
+            ProcessTarget::Regex(regex) => regex.is_match(&record.name),
+        }
+    }
+}
+
+// tree の時は子プロセスも対象にし、子から先に終了させる。自分自身は対象にしない
+pub fn find_targets(
+    records: &[ProcessRecord],
+    target: &ProcessTarget,
+    tree: bool,
+) -> Vec<ProcessRecord> {
+    let own_pid = process::id();
+    let mut pids: Vec<u32> = records
+        .iter()
+        .filter(|record| target.matches(record))
+        .map(|record| record.pid)
+        .collect();
+    pids.sort();
+
+    if tree {
+        let mut index = 0;
+        while index < pids.len() {
+            let parent = pids[index];
+            for record in records {
+                if record.parent_pid == Some(parent) && !pids.contains(&record.pid) {
+                    pids.push(record.pid);
+                }
+            }
+            index += 1;
+        }
+        pids.reverse();
+    }
+
+    pids.iter()
+        .filter(|pid| **pid != own_pid)
+        .filter_map(|pid| records.iter().find(|record| record.pid == *pid))
+        .cloned()
+        .collect()
+}
+
+pub struct KillOptions {
+    pub force: bool,
+    pub timeout: Duration,
+}
+
+// ゾンビは終了済みとして扱う
+fn running(sys: &mut System, pids: &[u32]) -> Vec<u32> {
+    let sys_pids: Vec<Pid> = pids.iter().map(|pid| Pid::from_u32(*pid)).collect();
+    sys.refresh_processes(ProcessesToUpdate::Some(&sys_pids), true);
+    pids.iter()
+        .copied()
+        .filter(|pid| {
+            sys.process(Pid::from_u32(*pid))
+                .is_some_and(|process| process.status() != ProcessStatus::Zombie)
+        })
+        .collect()
+}
+
+fn wait_for_exit(pids: &[u32], timeout: Duration) -> Vec<u32> {
+    let started = Instant::now();
+    let mut sys = System::new();
+
+    loop {
+        let remaining = running(&mut sys, pids);
+        if remaining.is_empty() || started.elapsed() >= timeout {
+            return remaining;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+// 送れなかった PID は待たずに、残っているかだけ確認する
+fn signal_and_wait(
+    pids: &[u32],
+    timeout: Duration,
+    errors: &mut HashMap<u32, String>,
+    send: impl Fn(u32) -> Result<(), JweError>,
+) -> Vec<u32> {
+    let mut signalled = Vec::new();
+    let mut failed = Vec::new();
+    for pid in pids {
+        match send(*pid) {
+            Ok(_) => signalled.push(*pid),
+            Err(e) => {
+                errors.insert(*pid, e.to_string());
+                failed.push(*pid);
+            }
+        }
+    }
+
+    let mut remaining = wait_for_exit(&signalled, timeout);
+    remaining.extend(running(&mut System::new(), &failed));
+    remaining
+}
+
+// 1 回目は終了要求、残ったものは強制終了し、最後に本当に終わったかを確認する
+pub fn kill(
+    platform: &impl Platform,
+    targets: &[ProcessRecord],
+    options: &KillOptions,
+) -> Vec<KillRecord> {
+    let mut errors = HashMap::new();
+    let mut methods = HashMap::new();
+    let mut pending: Vec<u32> = targets.iter().map(|target| target.pid).collect();
+
+    if !options.force {
+        let remaining = signal_and_wait(&pending, options.timeout, &mut errors, |pid| {
+            platform.terminate_pid(pid)
+        });
+        for pid in pending.iter().filter(|pid| !remaining.contains(pid)) {
+            methods.insert(*pid, "terminated");
+        }
+        pending = remaining;
+    }
+
+    if !pending.is_empty() {
+        let remaining = signal_and_wait(&pending, options.timeout, &mut errors, |pid| {
+            platform.kill_pid(pid)
+        });
+        for pid in pending.iter().filter(|pid| !remaining.contains(pid)) {
+            methods.insert(*pid, "killed");
+        }
+    }
+
+    targets
+        .iter()
+        .map(|target| match methods.get(&target.pid) {
+            Some(method) => KillRecord {
+                pid: target.pid,
+                name: target.name.clone(),
+                success: true,
+                message: method.to_string(),
+            },
+            None => KillRecord {
+                pid: target.pid,
+                name: target.name.clone(),
+                success: false,
+                message: errors.get(&target.pid).cloned().unwrap_or_else(|| {
+                    format!("still running after {}s", options.timeout.as_secs())
+                }),
+            },
+        })
+        .collect()
+}
@@ -0,0 +1,64 @@
+use std::io::{self, IsTerminal, Write};
+
+use super::error::JweError;
+
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal()
+}
+
+// 引数で値が渡されていればそれを使い、無ければ stdin が端末の時だけ入力を求める
+pub fn value_or_prompt(
+    value: Option<&str>,
+    message: &str,
+    label: &str,
+) -> Result<String, JweError> {
+    if let Some(value) = value {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(JweError::InvalidInput(format!(
+                "{} must not be empty",
+                label
+            )));
+        }
+        return Ok(value.to_string());
+    }
+
+    if !is_interactive() {
+        return Err(JweError::InvalidInput(format!(
+            "Missing {} (pass it as an argument when stdin is not a terminal)",
+            label
+        )));
+    }
+
+    println!("{}", message);
+    let input = read_line(&format!("Enter the {}: ", label))?;
+    if input.is_empty() {
+        return Err(JweError::InvalidInput(format!(
+            "{} must not be empty",
+            label
+        )));
+    }
+    Ok(input)
+}
+
+// y か yes の時だけ true を返す。端末でなければ確認できないのでエラーにする
+pub fn confirm(message: &str) -> Result<bool, JweError> {
+    if !is_interactive() {
+        return Err(JweError::InvalidInput(format!(
+            "{} (pass --yes to confirm when stdin is not a terminal)",
+            message
+        )));
+    }
+
+    let input = read_line(&format!("{} [y/N]: ", message))?;
+    Ok(matches!(input.to_lowercase().as_str(), "y" | "yes"))
+}
+
+pub fn read_line(prompt: &str) -> Result<String, JweError> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_string())
+}
@@ -0,0 +1,304 @@
+use serde::Serialize;
+
+use super::data_controller::{LaunchMode, LauncherEntry};
+use super::memory_controller::PressureLevel;
+
+// --output json/yaml/csv で出力する結果の型
+// csv でも出せるように入れ子にしない
+
+#[derive(Serialize, Debug)]
+pub struct CpuRecord {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct MemoryRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub free: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_total: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_used: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_free: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_used_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<PressureLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure_percent: Option<f64>,
+}
+
+// mem show --top の時だけ使う。CSV では memory だけを出す
+#[derive(Serialize, Debug)]
+pub struct MemoryReport {
+    #[serde(flatten)]
+    pub memory: MemoryRecord,
+    pub processes: Vec<ProcessRecord>,
+}
+
+// watch や monitor report で出す min/avg/max。unit は "%"、"bytes"、"bytes/s"
+#[derive(Serialize, Debug)]
+pub struct SummaryRecord {
+    pub metric: String,
+    pub unit: String,
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+    pub samples: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ProcessUsageRecord {
+    pub name: String,
+    pub samples: u64,
+    pub avg_cpu: f64,
+    pub peak_cpu: f64,
+    pub peak_memory: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MonitorReport {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub samples: usize,
+    pub metrics: Vec<SummaryRecord>,
+    pub processes: Vec<ProcessUsageRecord>,
+}
+
+// sysinfo の 1 行目。CSV ではこれだけを出す
+#[derive(Serialize, Debug)]
+pub struct SystemOverviewRecord {
+    pub os_name: Option<String>,
+    pub os_version: Option<String>,
+    pub kernel: Option<String>,
+    pub hostname: Option<String>,
+    pub arch: Option<String>,
+    pub uptime: u64,
+    pub boot_time: Option<String>,
+    pub cpu_brand: String,
+    pub physical_cores: Option<usize>,
+    pub logical_cores: usize,
+    pub cpu_frequency: u64,
+    pub memory_total: u64,
+    pub memory_used: u64,
+    pub memory_available: u64,
+    pub swap_total: u64,
+    pub swap_used: u64,
+    // Windows には load average が無い
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_one: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_five: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_fifteen: Option<f64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SystemReport {
+    #[serde(flatten)]
+    pub overview: SystemOverviewRecord,
+    pub disks: Vec<DiskRecord>,
+    pub networks: Vec<NetworkRecord>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DiskRecord {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub kind: String,
+    pub total: u64,
+    pub used: u64,
+    pub available: u64,
+    pub used_percent: f64,
+    pub removable: bool,
+    pub read_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub low: Option<bool>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct NetworkRecord {
+    pub interface: String,
+    pub mac: String,
+    pub ips: String,
+    pub received: u64,
+    pub transmitted: u64,
+    pub packets_received: u64,
+    pub packets_transmitted: u64,
+    pub errors_received: u64,
+    pub errors_transmitted: u64,
+}
+
+// 温度は摂氏。取れなかった値は None
+#[derive(Serialize, Debug)]
+pub struct SensorRecord {
+    pub label: String,
+    pub temperature: Option<f32>,
+    pub max: Option<f32>,
+    pub critical: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alert: Option<bool>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FileRecord {
+    pub name: String,
+    pub path: String,
+    // dir / file / symlink / other
+    pub kind: String,
+    pub size: u64,
+    pub modified: Option<String>,
+    pub permissions: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TreeRecord {
+    pub path: String,
+    pub name: String,
+    pub depth: usize,
+    pub kind: String,
+    pub size: u64,
+}
+
+// size と files は子の中身を再帰的に合計したもの
+#[derive(Serialize, Debug)]
+pub struct DuRecord {
+    pub path: String,
+    pub name: String,
+    pub kind: String,
+    pub size: u64,
+    pub files: u64,
+    pub percent: f64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RemoveRecord {
+    pub path: String,
+    pub kind: String,
+    pub size: u64,
+    pub trash_id: Option<String>,
+    pub success: bool,
+    pub message: String,
+}
+
+// original_path は消した時の絶対パス
+#[derive(Serialize, Debug)]
+pub struct TrashRecord {
+    pub id: String,
+    pub name: String,
+    pub original_path: String,
+    pub deleted_at: String,
+    pub kind: String,
+    pub size: u64,
+    pub files: u64,
+}
+
+// default は設定ファイルが無い時に使われる値
+#[derive(Serialize, Debug)]
+pub struct ConfigRecord {
+    pub key: String,
+    pub value: String,
+    pub default: String,
+    pub description: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FavoriteRecord {
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LauncherRecord {
+    pub name: String,
+    pub program: String,
+    pub args: String,
+    pub cwd: String,
+    pub env: String,
+    pub description: String,
+    pub tags: String,
+    pub mode: LaunchMode,
+    pub elevated: bool,
+}
+
+impl LauncherRecord {
+    pub fn new(name: String, entry: &LauncherEntry) -> Self {
+        let mut env: Vec<String> = entry
+            .env
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        env.sort();
+
+        LauncherRecord {
+            name,
+            program: entry.program.clone(),
+            args: entry.args.join(" "),
+            cwd: entry.cwd.clone().unwrap_or_default(),
+            env: env.join(";"),
+            description: entry.description.clone().unwrap_or_default(),
+            tags: entry.tags.join(","),
+            mode: entry.mode,
+            elevated: entry.elevated,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ProcessRecord {
+    pub pid: u32,
+    pub parent_pid: Option<u32>,
+    pub name: String,
+    pub exe: Option<String>,
+    pub cpu_usage: f32,
+    pub memory: u64,
+    pub user: Option<String>,
+    pub start_time: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct KillRecord {
+    pub pid: u32,
+    pub name: String,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SidRecord {
+    pub sid: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct GroupRunRecord {
+    pub step: usize,
+    pub launcher: String,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct GroupItemRecord {
+    pub group: String,
+    pub step: usize,
+    pub launcher: String,
+    pub delay_ms: u64,
+    pub wait_started: bool,
+    pub timeout_secs: u64,
+}
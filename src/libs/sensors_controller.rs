@@ -0,0 +1,114 @@
+use sysinfo::{Component, Components};
+use tabled::{builder::Builder, settings::Style};
+
+use super::error::JweError;
+use super::records::SensorRecord;
+
+// CPU の温度センサーによく付くラベル (coretemp, k10temp, Windows の CPU 系など)
+const CPU_LABELS: [&str; 6] = ["cpu", "core", "package", "coretemp", "k10temp", "tctl"];
+
+pub struct SensorAlert {
+    pub warn_above: Option<f32>,
+    pub critical: bool,
+}
+
+impl SensorAlert {
+    fn enabled(&self) -> bool {
+        self.warn_above.is_some() || self.critical
+    }
+}
+
+// 取得できなかった温度は NaN で返ってくるので None にそろえる
+fn celsius(value: f32) -> Option<f32> {
+    (value.is_finite() && value > 0.0).then_some(value)
+}
+
+fn record(component: &Component, alert: &SensorAlert) -> SensorRecord {
+    let temperature = celsius(component.temperature());
+    let critical = component.critical().and_then(celsius);
+
+    SensorRecord {
+        label: component.label().to_string(),
+        temperature,
+        max: celsius(component.max()),
+        critical,
+        alert: alert.enabled().then(|| {
+            temperature.is_some_and(|temperature| {
+                alert.warn_above.is_some_and(|limit| temperature >= limit)
+                    || (alert.critical && critical.is_some_and(|critical| temperature >= critical))
+            })
+        }),
+    }
+}
+
+pub fn list(alert: &SensorAlert) -> Vec<SensorRecord> {
+    let components = Components::new_with_refreshed_list();
+    let mut records: Vec<SensorRecord> = components
+        .list()
+        .iter()
+        .map(|component| record(component, alert))
+        .collect();
+    records.sort_by(|a, b| a.label.cmp(&b.label));
+    records
+}
+
+// CPU らしいセンサーの中で一番高い温度
+pub fn cpu_temperature() -> Option<f32> {
+    let components = Components::new_with_refreshed_list();
+    components
+        .list()
+        .iter()
+        .filter(|component| {
+            let label = component.label().to_lowercase();
+            CPU_LABELS.iter().any(|name| label.contains(name))
+        })
+        .filter_map(|component| celsius(component.temperature()))
+        .max_by(f32::total_cmp)
+}
+
+pub fn format_celsius(value: Option<f32>) -> String {
+    value
+        .map(|value| format!("{:.1}°C", value))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+pub fn table(records: &[SensorRecord]) -> String {
+    if records.is_empty() {
+        return "No temperature sensors found".to_string();
+    }
+
+    let mut builder = Builder::default();
+    builder.push_record(["Label", "Temperature", "Max", "Critical"]);
+    for record in records {
+        let mut temperature = format_celsius(record.temperature);
+        if record.alert == Some(true) {
+            temperature.push_str(" HOT");
+        }
+        builder.push_record([
+            record.label.clone(),
+            temperature,
+            format_celsius(record.max),
+            format_celsius(record.critical),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii_rounded());
+    table.to_string()
+}
+
+// 閾値を超えたセンサーがあればエラーにする
+pub fn check(records: &[SensorRecord]) -> Result<(), JweError> {
+    let hot: Vec<String> = records
+        .iter()
+        .filter(|record| record.alert == Some(true))
+        .map(|record| format!("{} ({})", record.label, format_celsius(record.temperature)))
+        .collect();
+
+    if hot.is_empty() {
+        return Ok(());
+    }
+    Err(JweError::Threshold(format!(
+        "Temperature over the limit on {}",
+        hot.join(", ")
+    )))
+}
@@ -0,0 +1,162 @@
+use std::fs;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use once_cell::sync::OnceCell;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::error::JweError;
+use super::logger_control;
+
+pub const APP_DIR_NAME: &str = "johma_windows_enhanced";
+pub const DATA_DIR_ENV: &str = "JWE_DATA_DIR";
+
+static DATA_ROOT: OnceCell<PathBuf> = OnceCell::new();
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// --data-dir > JWE_DATA_DIR > %LOCALAPPDATA%\johma_windows_enhanced の順で決める
+// 最初に data_root() が呼ばれる前に設定する必要がある
+pub fn set_data_root(path: PathBuf) {
+    if DATA_ROOT.set(path).is_err() {
+        logger_control::log(
+            "Data directory already initialized",
+            logger_control::LogLevel::WARNING,
+        );
+    }
+}
+
+pub fn data_root() -> Result<&'static Path, JweError> {
+    DATA_ROOT
+        .get_or_try_init(|| match std::env::var_os(DATA_DIR_ENV) {
+            Some(dir) if !dir.is_empty() => Ok(PathBuf::from(dir)),
+            _ => dirs::data_local_dir()
+                .map(|local_data| local_data.join(APP_DIR_NAME))
+                .ok_or_else(|| {
+                    JweError::NotFound("Failed to get local app data directory".to_string())
+                }),
+        })
+        .map(PathBuf::as_path)
+}
+
+pub struct Store<T> {
+    path: PathBuf,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Store<T>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    // データディレクトリ直下のファイル
+    pub fn new(file_name: &str) -> Result<Self, JweError> {
+        Ok(Self::at(data_root()?.join(file_name)))
+    }
+
+    pub fn at(path: PathBuf) -> Self {
+        Store {
+            path,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    pub fn read(&self) -> Result<T, JweError> {
+        let contents =
+            fs::read_to_string(&self.path).map_err(|e| JweError::File(self.path.clone(), e))?;
+
+        toml::from_str(&contents)
+            .map_err(|e| JweError::Parse(format!("Failed to parse {}: {}", self.path.display(), e)))
+    }
+
+    // 一時ファイルに書いてから rename するので途中で落ちても壊れない
+    pub fn write(&self, value: &T) -> Result<(), JweError> {
+        let toml = toml::to_string(value).map_err(|e| {
+            JweError::Parse(format!(
+                "Failed to serialize {}: {}",
+                self.path.display(),
+                e
+            ))
+        })?;
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| JweError::File(parent.to_path_buf(), e))?;
+        }
+
+        let tmp_path = self.tmp_path();
+        fs::write(&tmp_path, toml).map_err(|e| JweError::File(tmp_path.clone(), e))?;
+
+        if let Err(e) = fs::rename(&tmp_path, &self.path) {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(JweError::File(self.path.clone(), e));
+        }
+
+        Ok(())
+    }
+
+    // ファイルが無ければデフォルト値で作成する。作成した場合は true
+    pub fn init(&self) -> Result<bool, JweError> {
+        if self.exists() {
+            return Ok(false);
+        }
+
+        self.write(&T::default())?;
+        Ok(true)
+    }
+
+    // 同時に書き込むプロセス・スレッド同士で一時ファイルが衝突しないよう pid と連番を付ける
+    fn tmp_path(&self) -> PathBuf {
+        let mut file_name = self.path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        self.path.with_file_name(file_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+    struct Sample {
+        name: String,
+        count: u32,
+    }
+
+    #[test]
+    fn write_then_read_round_trips_without_leftover_tmp() {
+        let root = std::env::temp_dir().join(format!("jwe-store-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let store: Store<Sample> = Store::at(root.join("sample.toml"));
+        let value = Sample {
+            name: "jwe".to_string(),
+            count: 3,
+        };
+        store.write(&value).unwrap();
+        store.write(&value).unwrap();
+
+        assert_eq!(store.read().unwrap(), value);
+
+        let leftovers: Vec<_> = fs::read_dir(&root)
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
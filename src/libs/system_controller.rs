@@ -0,0 +1,131 @@
+use chrono::{Local, TimeZone};
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
+use tabled::{builder::Builder, settings::Style};
+
+use super::disk_controller;
+use super::network_controller;
+use super::records::{SystemOverviewRecord, SystemReport};
+use super::units;
+
+pub fn report() -> SystemReport {
+    let sys = System::new_with_specifics(
+        RefreshKind::new()
+            .with_cpu(CpuRefreshKind::new().with_frequency())
+            .with_memory(MemoryRefreshKind::everything()),
+    );
+    // Windows では常に 0 が返るので出さない
+    let load_average = System::load_average();
+    let load = |value: f64| (!cfg!(target_os = "windows")).then_some(value);
+
+    let overview = SystemOverviewRecord {
+        os_name: System::name(),
+        os_version: System::long_os_version(),
+        kernel: System::kernel_version(),
+        hostname: System::host_name(),
+        arch: System::cpu_arch(),
+        uptime: System::uptime(),
+        boot_time: Local
+            .timestamp_opt(System::boot_time() as i64, 0)
+            .single()
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string()),
+        cpu_brand: sys
+            .cpus()
+            .first()
+            .map(|cpu| cpu.brand().trim().to_string())
+            .unwrap_or_default(),
+        physical_cores: sys.physical_core_count(),
+        logical_cores: sys.cpus().len(),
+        cpu_frequency: sys
+            .cpus()
+            .iter()
+            .map(|cpu| cpu.frequency())
+            .max()
+            .unwrap_or(0),
+        memory_total: sys.total_memory(),
+        memory_used: sys.used_memory(),
+        memory_available: sys.available_memory(),
+        swap_total: sys.total_swap(),
+        swap_used: sys.used_swap(),
+        load_one: load(load_average.one),
+        load_five: load(load_average.five),
+        load_fifteen: load(load_average.fifteen),
+    };
+
+    SystemReport {
+        overview,
+        disks: disk_controller::list(None),
+        networks: network_controller::list(),
+    }
+}
+
+// 3d 4h 12m の形式
+pub fn format_uptime(secs: u64) -> String {
+    let days = secs / 86_400;
+    let hours = secs % 86_400 / 3_600;
+    let minutes = secs % 3_600 / 60;
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn usage(used: u64, total: u64) -> String {
+    format!(
+        "{} / {} ({:.1}%)",
+        units::format_bytes(used),
+        units::format_bytes(total),
+        units::percent(used, total)
+    )
+}
+
+pub fn render(report: &SystemReport) -> String {
+    let overview = &report.overview;
+    let unknown = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+
+    let mut rows = vec![
+        ("OS", unknown(&overview.os_version)),
+        ("Kernel", unknown(&overview.kernel)),
+        ("Hostname", unknown(&overview.hostname)),
+        ("Architecture", unknown(&overview.arch)),
+        ("Uptime", format_uptime(overview.uptime)),
+        ("Boot time", unknown(&overview.boot_time)),
+        ("CPU", overview.cpu_brand.clone()),
+        (
+            "Cores",
+            match overview.physical_cores {
+                Some(physical) => {
+                    format!("{} physical / {} logical", physical, overview.logical_cores)
+                }
+                None => format!("{} logical", overview.logical_cores),
+            },
+        ),
+        ("Frequency", format!("{} MHz", overview.cpu_frequency)),
+        ("Memory", usage(overview.memory_used, overview.memory_total)),
+        ("Swap", usage(overview.swap_used, overview.swap_total)),
+    ];
+    if let (Some(one), Some(five), Some(fifteen)) =
+        (overview.load_one, overview.load_five, overview.load_fifteen)
+    {
+        rows.push((
+            "Load average",
+            format!("{:.2} {:.2} {:.2}", one, five, fifteen),
+        ));
+    }
+
+    let mut builder = Builder::default();
+    for (key, value) in rows {
+        builder.push_record([key.to_string(), value]);
+    }
+    let mut summary = builder.build();
+    summary.with(Style::ascii_rounded());
+
+    format!(
+        "{}\nDisks\n{}\nNetworks\n{}",
+        summary,
+        disk_controller::table(&report.disks),
+        network_controller::table(&report.networks)
+    )
+}
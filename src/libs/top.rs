@@ -0,0 +1,293 @@
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use sysinfo::{System, Users, MINIMUM_CPU_UPDATE_INTERVAL};
+use tabled::{builder::Builder, settings::Style};
+
+use super::error::JweError;
+use super::logger_control;
+use super::platform::Platform;
+use super::process_controller::{self, KillOptions, ProcessFilter, ProcessSort};
+use super::prompt;
+use super::records::ProcessRecord;
+use super::units;
+
+// ヘッダー 1 行、表の上下の枠と見出しで 3 行、下のステータス 1 行
+const RESERVED_ROWS: usize = 5;
+const KILL_TIMEOUT: Duration = Duration::from_secs(3);
+
+pub struct TopOptions {
+    pub interval: Duration,
+    pub sort: ProcessSort,
+    pub name: Option<String>,
+}
+
+enum Mode {
+    Normal,
+    Filter(String),
+    ConfirmKill(ProcessRecord),
+}
+
+struct State {
+    sort: ProcessSort,
+    filter: ProcessFilter,
+    // 並び順が変わっても同じプロセスを選んだままにするため PID で持つ
+    selected_pid: Option<u32>,
+    mode: Mode,
+    status: String,
+    records: Vec<ProcessRecord>,
+}
+
+impl State {
+    fn visible(&self) -> Vec<ProcessRecord> {
+        process_controller::select(self.records.clone(), &self.filter, self.sort, None)
+    }
+
+    fn selected_index(&self, visible: &[ProcessRecord]) -> usize {
+        self.selected_pid
+            .and_then(|pid| visible.iter().position(|record| record.pid == pid))
+            .unwrap_or(0)
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let visible = self.visible();
+        if visible.is_empty() {
+            return;
+        }
+        let index = self
+            .selected_index(&visible)
+            .saturating_add_signed(delta)
+            .min(visible.len() - 1);
+        self.selected_pid = Some(visible[index].pid);
+    }
+}
+
+// パニックやエラーで抜けた時も端末を元に戻す
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self, JweError> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub fn run(platform: &impl Platform, options: TopOptions) -> Result<(), JweError> {
+    if !io::stdout().is_terminal() || !prompt::is_interactive() {
+        return Err(JweError::InvalidInput(
+            "proc top needs an interactive terminal".to_string(),
+        ));
+    }
+
+    let mut sys = System::new();
+    let users = Users::new_with_refreshed_list();
+    let mut state = State {
+        sort: options.sort,
+        filter: ProcessFilter {
+            name: options.name,
+            user: None,
+        },
+        selected_pid: None,
+        mode: Mode::Normal,
+        status: String::new(),
+        records: Vec::new(),
+    };
+
+    let _guard = TerminalGuard::enter()?;
+    logger_control::log("Proc top started", logger_control::LogLevel::INFO);
+
+    // CPU 使用率は差分で出るので、最初の 1 回は短い間隔で更新する
+    process_controller::refresh(&mut sys);
+    let mut next_refresh = Instant::now() + MINIMUM_CPU_UPDATE_INTERVAL;
+
+    loop {
+        if Instant::now() >= next_refresh {
+            process_controller::refresh(&mut sys);
+            state.records = process_controller::records(&sys, &users);
+            next_refresh = Instant::now() + options.interval;
+        }
+        draw(&state, options.interval)?;
+
+        let timeout = next_refresh.saturating_duration_since(Instant::now());
+        if !event::poll(timeout)? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !handle_key(platform, &mut state, key) {
+                break;
+            }
+        }
+    }
+
+    logger_control::log("Proc top finished", logger_control::LogLevel::INFO);
+    Ok(())
+}
+
+// false を返したら終了する
+fn handle_key(platform: &impl Platform, state: &mut State, key: KeyEvent) -> bool {
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+        return false;
+    }
+
+    match std::mem::replace(&mut state.mode, Mode::Normal) {
+        Mode::Normal => {
+            state.status.clear();
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return false,
+                KeyCode::Char('c') => state.sort = ProcessSort::Cpu,
+                KeyCode::Char('m') => state.sort = ProcessSort::Mem,
+                KeyCode::Char('p') => state.sort = ProcessSort::Pid,
+                KeyCode::Up => state.move_selection(-1),
+                KeyCode::Down => state.move_selection(1),
+                KeyCode::PageUp => state.move_selection(-10),
+                KeyCode::PageDown => state.move_selection(10),
+                KeyCode::Home => state.move_selection(isize::MIN),
+                KeyCode::End => state.move_selection(isize::MAX),
+                KeyCode::Char('/') => {
+                    state.mode = Mode::Filter(state.filter.name.clone().unwrap_or_default())
+                }
+                KeyCode::Char('k') | KeyCode::Delete => {
+                    let visible = state.visible();
+                    if let Some(record) = visible.get(state.selected_index(&visible)) {
+                        state.mode = Mode::ConfirmKill(record.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+        Mode::Filter(mut text) => match key.code {
+            KeyCode::Enter => {
+                let text = text.trim().to_string();
+                state.filter.name = if text.is_empty() { None } else { Some(text) };
+            }
+            KeyCode::Esc => {}
+            KeyCode::Backspace => {
+                text.pop();
+                state.mode = Mode::Filter(text);
+            }
+            KeyCode::Char(c) => {
+                text.push(c);
+                state.mode = Mode::Filter(text);
+            }
+            _ => state.mode = Mode::Filter(text),
+        },
+        Mode::ConfirmKill(record) => {
+            if key.code == KeyCode::Char('y') {
+                state.status = kill(platform, &record);
+            }
+        }
+    }
+    true
+}
+
+fn kill(platform: &impl Platform, record: &ProcessRecord) -> String {
+    let results = process_controller::kill(
+        platform,
+        std::slice::from_ref(record),
+        &KillOptions {
+            force: false,
+            timeout: KILL_TIMEOUT,
+        },
+    );
+    results
+        .first()
+        .map(|result| format!("PID {} {}: {}", result.pid, result.name, result.message))
+        .unwrap_or_default()
+}
+
+fn status_line(state: &State) -> String {
+    match &state.mode {
+        Mode::Filter(text) => format!("Filter by name (Enter apply, Esc cancel): {}", text),
+        Mode::ConfirmKill(record) => format!("Kill PID {} {}? (y/N)", record.pid, record.name),
+        Mode::Normal if !state.status.is_empty() => state.status.clone(),
+        Mode::Normal => "q quit  c/m/p sort  / filter  Up/Down select  k kill".to_string(),
+    }
+}
+
+fn render_table(records: &[ProcessRecord]) -> String {
+    let mut builder = Builder::default();
+    builder.push_record(["PID", "Name", "CPU %", "Memory", "User"]);
+    for record in records {
+        builder.push_record([
+            record.pid.to_string(),
+            record.name.clone(),
+            format!("{:.1}", record.cpu_usage),
+            units::format_bytes(record.memory),
+            record.user.clone().unwrap_or_default(),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii_rounded());
+    table.to_string()
+}
+
+fn draw(state: &State, interval: Duration) -> Result<(), JweError> {
+    let (width, height) = terminal::size()?;
+    let width = width as usize;
+    let rows = (height as usize).saturating_sub(RESERVED_ROWS).max(1);
+
+    let visible = state.visible();
+    let selected = state.selected_index(&visible);
+    let offset = selected.saturating_sub(rows - 1);
+    let page = &visible[offset.min(visible.len())..(offset + rows).min(visible.len())];
+
+    let sort = state
+        .sort
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default();
+    let header = format!(
+        "jwe proc top - {} processes - sort: {} - filter: {} - every {}s",
+        visible.len(),
+        sort,
+        state.filter.name.as_deref().unwrap_or("none"),
+        interval.as_secs()
+    );
+
+    let mut stdout = io::stdout();
+    let mut lines = vec![header];
+    lines.extend(render_table(page).lines().map(str::to_string));
+
+    for (row, line) in lines.iter().enumerate() {
+        let line: String = line.chars().take(width).collect();
+        // 表の 1 行目は枠、2 行目は見出しなので、3 行目からが各プロセス
+        let highlight = !page.is_empty() && row == selected - offset + 3;
+        queue!(stdout, MoveTo(0, row as u16))?;
+        if highlight {
+            queue!(stdout, SetAttribute(Attribute::Reverse))?;
+        }
+        queue!(
+            stdout,
+            Print(line),
+            SetAttribute(Attribute::Reset),
+            Clear(ClearType::UntilNewLine)
+        )?;
+    }
+
+    let status: String = status_line(state).chars().take(width).collect();
+    queue!(
+        stdout,
+        MoveTo(0, lines.len() as u16),
+        Clear(ClearType::FromCursorDown),
+        MoveTo(0, height.saturating_sub(1)),
+        Print(status)
+    )?;
+    stdout.flush()?;
+    Ok(())
+}
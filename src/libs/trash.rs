@@ -0,0 +1,595 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use clap::ValueEnum;
+use glob::MatchOptions;
+use serde::{Deserialize, Serialize};
+use tabled::{builder::Builder, settings::Style};
+
+use super::error::JweError;
+use super::file_controller;
+use super::logger_control;
+use super::records::{RemoveRecord, TrashRecord};
+use super::store::{self, Store};
+use super::units;
+
+const TRASH_DIR: &str = "trash";
+const FILES_DIR: &str = "files";
+const INFO_DIR: &str = "info";
+// これより多くのファイルを消す時は確認する
+pub const CONFIRM_THRESHOLD: u64 = 10;
+
+// trash/info/<id>.toml。中身は trash/files/<id> に置く
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TrashInfo {
+    pub original_path: String,
+    pub deleted_at: i64,
+    pub kind: String,
+    pub size: u64,
+    pub files: u64,
+}
+
+pub struct RemoveTarget {
+    pub path: PathBuf,
+    pub kind: String,
+    pub size: u64,
+    pub files: u64,
+}
+
+pub struct RemoveOptions {
+    pub permanent: bool,
+    pub dry_run: bool,
+}
+
+// 戻す場所に同じ名前のものがある時の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Conflict {
+    #[default]
+    Fail,
+    Rename,
+    Overwrite,
+}
+
+pub fn trash_root() -> Result<PathBuf, JweError> {
+    Ok(store::data_root()?.join(TRASH_DIR))
+}
+
+pub fn files_path(id: &str) -> Result<PathBuf, JweError> {
+    Ok(trash_root()?.join(FILES_DIR).join(id))
+}
+
+pub fn info_store(id: &str) -> Result<Store<TrashInfo>, JweError> {
+    Ok(Store::at(
+        trash_root()?.join(INFO_DIR).join(format!("{}.toml", id)),
+    ))
+}
+
+// シンボリックリンクはたどらない。(合計サイズ, ファイル数)
+pub fn usage(path: &Path) -> (u64, u64) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return (0, 0);
+    };
+    if !metadata.is_dir() {
+        return (metadata.len(), 1);
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries.flatten().fold((0, 0), |(size, files), entry| {
+                let (entry_size, entry_files) = usage(&entry.path());
+                (size + entry_size, files + entry_files)
+            })
+        })
+        .unwrap_or((0, 0))
+}
+
+// リンク自体を消すので、親だけ正規化してから名前をつなぐ
+fn resolve(path: &Path) -> Result<PathBuf, JweError> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| JweError::InvalidInput(format!("Refusing to remove {}", path.display())))?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let parent = fs::canonicalize(parent).map_err(|e| file_controller::not_found(parent, e))?;
+    Ok(parent.join(name))
+}
+
+// データディレクトリやゴミ箱そのものを消さないようにする
+fn check_safe(path: &Path) -> Result<(), JweError> {
+    let data_root = fs::canonicalize(store::data_root()?).unwrap_or_else(|_| {
+        store::data_root()
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    });
+    if path.parent().is_none() || data_root.starts_with(path) {
+        return Err(JweError::InvalidInput(format!(
+            "Refusing to remove {}",
+            path.display()
+        )));
+    }
+    if path.starts_with(data_root.join(TRASH_DIR)) {
+        return Err(JweError::InvalidInput(format!(
+            "{} is already in the trash (use jwe trash purge)",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+// Windows のシェルは * を展開しないので自分で展開する
+fn expand_pattern(pattern: &str) -> Result<Vec<PathBuf>, JweError> {
+    if !pattern.contains(['*', '?', '[']) {
+        return Ok(vec![PathBuf::from(pattern)]);
+    }
+    let paths: Vec<PathBuf> = glob::glob_with(
+        pattern,
+        MatchOptions {
+            require_literal_leading_dot: true,
+            ..MatchOptions::new()
+        },
+    )
+    .map_err(|e| JweError::InvalidInput(format!("Invalid pattern {}: {}", pattern, e)))?
+    .flatten()
+    .collect();
+
+    if paths.is_empty() {
+        return Err(JweError::NotFound(format!("No file matches {}", pattern)));
+    }
+    Ok(paths)
+}
+
+// 1 つでも消せないものがあれば何もしないうちにエラーにする
+pub fn targets(patterns: &[String], recursive: bool) -> Result<Vec<RemoveTarget>, JweError> {
+    let mut targets: Vec<RemoveTarget> = Vec::new();
+    for pattern in patterns {
+        for path in expand_pattern(pattern)? {
+            let metadata =
+                fs::symlink_metadata(&path).map_err(|e| file_controller::not_found(&path, e))?;
+            let path = resolve(&path)?;
+            check_safe(&path)?;
+            if metadata.is_dir() && !recursive {
+                return Err(JweError::InvalidInput(format!(
+                    "{} is a directory (use -r to remove it)",
+                    path.display()
+                )));
+            }
+            if targets.iter().any(|target| target.path == path) {
+                continue;
+            }
+
+            let (size, files) = usage(&path);
+            targets.push(RemoveTarget {
+                kind: file_controller::kind(&metadata).to_string(),
+                path,
+                size,
+                files,
+            });
+        }
+    }
+    Ok(targets)
+}
+
+fn format_deleted_at(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|time| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn trash_record(id: String, info: TrashInfo) -> TrashRecord {
+    TrashRecord {
+        name: Path::new(&info.original_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| info.original_path.clone()),
+        deleted_at: format_deleted_at(info.deleted_at),
+        original_path: info.original_path,
+        kind: info.kind,
+        size: info.size,
+        files: info.files,
+        id,
+    }
+}
+
+// 20240101120000-12 の連番の部分。文字列のままだと -12 が -2 より前に来る
+fn id_counter(id: &str) -> u64 {
+    id.rsplit_once('-')
+        .and_then(|(_, counter)| counter.parse().ok())
+        .unwrap_or(0)
+}
+
+// 新しく消したものから順に並べる。読めない情報ファイルは飛ばす
+fn entries() -> Result<Vec<(String, TrashInfo)>, JweError> {
+    let info_dir = trash_root()?.join(INFO_DIR);
+    let read_dir = match fs::read_dir(&info_dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(JweError::File(info_dir, e)),
+    };
+
+    let mut entries: Vec<(String, TrashInfo)> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "toml") {
+                return None;
+            }
+            let id = path.file_stem()?.to_string_lossy().to_string();
+            let info = Store::<TrashInfo>::at(path).read().ok()?;
+            Some((id, info))
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.1.deleted_at
+            .cmp(&a.1.deleted_at)
+            .then(id_counter(&b.0).cmp(&id_counter(&a.0)))
+    });
+    Ok(entries)
+}
+
+pub fn list() -> Result<Vec<TrashRecord>, JweError> {
+    Ok(entries()?
+        .into_iter()
+        .map(|(id, info)| trash_record(id, info))
+        .collect())
+}
+
+// ID が一致しなければ元のファイル名か元のパスで探す
+fn lookup(target: &str) -> Result<(String, TrashInfo), JweError> {
+    let mut entries = entries()?;
+    if let Some(index) = entries.iter().position(|(id, _)| id == target) {
+        return Ok(entries.swap_remove(index));
+    }
+
+    let mut matches: Vec<(String, TrashInfo)> = entries
+        .into_iter()
+        .filter(|(_, info)| {
+            let path = Path::new(&info.original_path);
+            info.original_path == target
+                || path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(target))
+        })
+        .collect();
+    match matches.len() {
+        0 => Err(JweError::NotFound(format!(
+            "No trash item matches {}",
+            target
+        ))),
+        1 => Ok(matches.remove(0)),
+        _ => Err(JweError::InvalidInput(format!(
+            "{} matches {} trash items, use one of the IDs: {}",
+            target,
+            matches.len(),
+            matches
+                .iter()
+                .map(|(id, _)| id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+// name (1).txt, name (2).txt ... の空いている名前
+fn free_name(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|n| path.with_file_name(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| fs::symlink_metadata(candidate).is_err())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+// 同じフォルダの中なら rename で確実に避けられる
+fn aside_name(path: &Path, id: &str) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    (1..)
+        .map(|n| path.with_file_name(format!(".{}.jwe-restore-{}-{}", name, id, n)))
+        .find(|candidate| fs::symlink_metadata(candidate).is_err())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+// 戻した先のパスを返す。元のフォルダがもう無ければ作り直す
+pub fn restore(target: &str, conflict: Conflict) -> Result<(TrashRecord, PathBuf), JweError> {
+    let (id, info) = lookup(target)?;
+    let source = files_path(&id)?;
+    if fs::symlink_metadata(&source).is_err() {
+        return Err(JweError::NotFound(format!(
+            "Trash item {} has no stored content",
+            id
+        )));
+    }
+
+    let original = PathBuf::from(&info.original_path);
+    // 上書きする時も、戻すのに成功するまでは今あるものを横に避けておくだけにする
+    let mut replaced = None;
+    let destination = if fs::symlink_metadata(&original).is_err() {
+        original
+    } else {
+        match conflict {
+            Conflict::Fail => {
+                return Err(JweError::InvalidInput(format!(
+                    "{} already exists (use --rename or --overwrite)",
+                    original.display()
+                )))
+            }
+            Conflict::Rename => free_name(&original),
+            Conflict::Overwrite => {
+                let aside = aside_name(&original, &id);
+                fs::rename(&original, &aside).map_err(|e| JweError::File(original.clone(), e))?;
+                replaced = Some(aside);
+                original
+            }
+        }
+    };
+
+    let moved = match destination.parent() {
+        Some(parent) => {
+            fs::create_dir_all(parent).map_err(|e| JweError::File(parent.to_path_buf(), e))
+        }
+        None => Ok(()),
+    }
+    .and_then(|_| move_path(&source, &destination).map_err(|e| JweError::File(source.clone(), e)));
+    if let Err(e) = moved {
+        if let Some(aside) = &replaced {
+            if let Err(rollback) = fs::rename(aside, &destination) {
+                logger_control::log(
+                    &format!(
+                        "Failed to put back {} as {}: {}",
+                        aside.display(),
+                        destination.display(),
+                        rollback
+                    ),
+                    logger_control::LogLevel::ERROR,
+                );
+            }
+        }
+        return Err(e);
+    }
+
+    if let Some(aside) = replaced {
+        if let Err(e) = remove_path(&aside) {
+            logger_control::log(
+                &format!("Failed to remove replaced {}: {}", aside.display(), e),
+                logger_control::LogLevel::WARNING,
+            );
+        }
+    }
+    let info_path = info_store(&id)?.path().to_path_buf();
+    fs::remove_file(&info_path).map_err(|e| JweError::File(info_path, e))?;
+    Ok((trash_record(id, info), destination))
+}
+
+// older_than が None なら全部
+pub fn purge_targets(older_than: Option<Duration>) -> Result<Vec<TrashRecord>, JweError> {
+    let now = Local::now().timestamp();
+    Ok(entries()?
+        .into_iter()
+        .filter(|(_, info)| {
+            older_than.is_none_or(|age| now - info.deleted_at >= age.as_secs() as i64)
+        })
+        .map(|(id, info)| trash_record(id, info))
+        .collect())
+}
+
+pub fn purge(targets: &[TrashRecord], dry_run: bool) -> Vec<RemoveRecord> {
+    targets
+        .iter()
+        .map(|target| {
+            let result = if dry_run {
+                Ok("dry run")
+            } else {
+                purge_item(&target.id).map(|_| "deleted")
+            };
+            let (success, message) = match result {
+                Ok(message) => (true, message.to_string()),
+                Err(e) => (false, e.to_string()),
+            };
+            RemoveRecord {
+                path: target.original_path.clone(),
+                kind: target.kind.clone(),
+                size: target.size,
+                trash_id: Some(target.id.clone()),
+                success,
+                message,
+            }
+        })
+        .collect()
+}
+
+// 中身を消してから情報を消す。途中で失敗しても一覧には残る
+fn purge_item(id: &str) -> Result<(), JweError> {
+    let content = files_path(id)?;
+    match remove_path(&content) {
+        Err(e) if e.kind() != ErrorKind::NotFound => return Err(JweError::File(content, e)),
+        _ => {}
+    }
+    let info_path = info_store(id)?.path().to_path_buf();
+    fs::remove_file(&info_path).map_err(|e| JweError::File(info_path, e))
+}
+
+pub fn list_table(records: &[TrashRecord]) -> String {
+    let mut builder = Builder::default();
+    builder.push_record(["ID", "Deleted", "Size", "Type", "Original Path"]);
+    for record in records {
+        builder.push_record([
+            record.id.clone(),
+            record.deleted_at.clone(),
+            units::format_bytes(record.size),
+            record.kind.clone(),
+            record.original_path.clone(),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii_rounded());
+    table.to_string()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(target_os = "windows")]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    let target = fs::read_link(from)?;
+    if fs::metadata(from).is_ok_and(|metadata| metadata.is_dir()) {
+        std::os::windows::fs::symlink_dir(target, to)
+    } else {
+        std::os::windows::fs::symlink_file(target, to)
+    }
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(from)?;
+    if metadata.is_symlink() {
+        copy_symlink(from, to)
+    } else if metadata.is_dir() {
+        fs::create_dir(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+fn remove_path(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+// 別のドライブには rename できないのでコピーしてから消す
+pub fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => {
+            if let Err(e) = copy_recursive(from, to) {
+                let _ = remove_path(to);
+                return Err(e);
+            }
+            remove_path(from)
+        }
+        result => result,
+    }
+}
+
+// 同じ秒に何件消しても重ならないように連番を付ける
+fn new_id() -> Result<String, JweError> {
+    let stamp = Local::now().format("%Y%m%d%H%M%S").to_string();
+    for n in 1.. {
+        let id = format!("{}-{}", stamp, n);
+        if !files_path(&id)?.exists() && !info_store(&id)?.exists() {
+            return Ok(id);
+        }
+    }
+    unreachable!()
+}
+
+fn put(target: &RemoveTarget) -> Result<String, JweError> {
+    let id = new_id()?;
+    let destination = files_path(&id)?;
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(|e| JweError::File(parent.to_path_buf(), e))?;
+    }
+
+    // 中身より先に情報を書いておけば、移動に失敗しても元の場所は分かる
+    let info = info_store(&id)?;
+    info.write(&TrashInfo {
+        original_path: target.path.display().to_string(),
+        deleted_at: Local::now().timestamp(),
+        kind: target.kind.clone(),
+        size: target.size,
+        files: target.files,
+    })?;
+    if let Err(e) = move_path(&target.path, &destination) {
+        let _ = fs::remove_file(info.path());
+        return Err(JweError::File(target.path.clone(), e));
+    }
+    Ok(id)
+}
+
+pub fn remove(targets: &[RemoveTarget], options: &RemoveOptions) -> Vec<RemoveRecord> {
+    targets
+        .iter()
+        .map(|target| {
+            let result = if options.dry_run {
+                Ok((None, "dry run"))
+            } else if options.permanent {
+                remove_path(&target.path)
+                    .map(|_| (None, "deleted"))
+                    .map_err(|e| e.to_string())
+            } else {
+                put(target)
+                    .map(|id| (Some(id), "moved to trash"))
+                    .map_err(|e| e.to_string())
+            };
+
+            let (trash_id, success, message) = match result {
+                Ok((trash_id, message)) => (trash_id, true, message.to_string()),
+                Err(message) => (None, false, message),
+            };
+            RemoveRecord {
+                path: target.path.display().to_string(),
+                kind: target.kind.clone(),
+                size: target.size,
+                trash_id,
+                success,
+                message,
+            }
+        })
+        .collect()
+}
+
+// 確認の前に何が消えるかを見せる
+pub fn targets_table(targets: &[RemoveTarget]) -> String {
+    let mut builder = Builder::default();
+    builder.push_record(["Size", "Files", "Type", "Path"]);
+    for target in targets {
+        builder.push_record([
+            units::format_bytes(target.size),
+            target.files.to_string(),
+            target.kind.clone(),
+            target.path.display().to_string(),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii_rounded());
+    table.to_string()
+}
+
+pub fn table(records: &[RemoveRecord]) -> String {
+    let mut builder = Builder::default();
+    builder.push_record(["Path", "Size", "Result", "Message"]);
+    for record in records {
+        builder.push_record([
+            record.path.clone(),
+            units::format_bytes(record.size),
+            if record.success { "ok" } else { "failed" }.to_string(),
+            match &record.trash_id {
+                Some(id) => format!("{} ({})", record.message, id),
+                None => record.message.clone(),
+            },
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii_rounded());
+    table.to_string()
+}
@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use ignore::{overrides::OverrideBuilder, WalkBuilder, WalkState};
+use tabled::{builder::Builder, settings::Style};
+
+use super::error::JweError;
+use super::file_controller;
+use super::logger_control;
+use super::records::{DuRecord, TreeRecord};
+use super::units;
+
+#[derive(Debug, Default)]
+pub struct WalkOptions {
+    pub depth: Option<usize>,
+    pub ignore: Vec<String>,
+    pub gitignore: bool,
+    pub all: bool,
+}
+
+// .gitignore は git リポジトリの外でも効かせる
+pub fn walker(root: &Path, options: &WalkOptions) -> Result<WalkBuilder, JweError> {
+    let mut overrides = OverrideBuilder::new(root);
+    for glob in &options.ignore {
+        overrides
+            .add(&format!("!{}", glob))
+            .map_err(|e| JweError::InvalidInput(format!("Invalid ignore glob {}: {}", glob, e)))?;
+    }
+    let overrides = overrides
+        .build()
+        .map_err(|e| JweError::InvalidInput(format!("Invalid ignore glob: {}", e)))?;
+
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(!options.all)
+        .parents(options.gitignore)
+        .ignore(options.gitignore)
+        .git_ignore(options.gitignore)
+        .git_global(options.gitignore)
+        .git_exclude(options.gitignore)
+        .require_git(false)
+        .overrides(overrides)
+        .max_depth(options.depth);
+    Ok(builder)
+}
+
+pub fn check_root(root: &Path) -> Result<(), JweError> {
+    fs::metadata(root)
+        .map(|_| ())
+        .map_err(|e| file_controller::not_found(root, e))
+}
+
+fn log_skipped(skipped: u64) {
+    if skipped > 0 {
+        logger_control::log(
+            &format!("Skipped {} entries that could not be read", skipped),
+            logger_control::LogLevel::WARNING,
+        );
+    }
+}
+
+// 深さ優先、同じ階層は名前順。ルート自身は含まない
+pub fn tree(root: &Path, options: &WalkOptions) -> Result<Vec<TreeRecord>, JweError> {
+    check_root(root)?;
+    let mut builder = walker(root, options)?;
+    builder.sort_by_file_name(|a, b| {
+        a.to_string_lossy()
+            .to_lowercase()
+            .cmp(&b.to_string_lossy().to_lowercase())
+    });
+
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    for entry in builder.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => {
+                skipped += 1;
+                continue;
+            }
+        };
+        if entry.depth() == 0 {
+            continue;
+        }
+        let metadata = entry.metadata().ok();
+        records.push(TreeRecord {
+            path: entry.path().display().to_string(),
+            name: entry.file_name().to_string_lossy().to_string(),
+            depth: entry.depth(),
+            kind: metadata
+                .as_ref()
+                .map(file_controller::kind)
+                .unwrap_or("other")
+                .to_string(),
+            size: metadata.map(|metadata| metadata.len()).unwrap_or(0),
+        });
+    }
+    log_skipped(skipped);
+    Ok(records)
+}
+
+pub fn render_tree(root: &Path, records: &[TreeRecord]) -> String {
+    // 後ろから見て、同じ親の下にまだ兄弟が続くかどうかを調べる
+    let mut is_last = vec![false; records.len()];
+    let mut sibling_after: Vec<bool> = Vec::new();
+    for (index, record) in records.iter().enumerate().rev() {
+        sibling_after.resize(record.depth + 1, false);
+        is_last[index] = !sibling_after[record.depth];
+        sibling_after[record.depth] = true;
+    }
+
+    let mut lines = vec![root.display().to_string()];
+    let mut ancestors_last: Vec<bool> = Vec::new();
+    let (mut dirs, mut files) = (0, 0);
+    for (record, last) in records.iter().zip(is_last) {
+        ancestors_last.truncate(record.depth - 1);
+        let mut line: String = ancestors_last
+            .iter()
+            .map(|ancestor_last| if *ancestor_last { "    " } else { "│   " })
+            .collect();
+        line.push_str(if last { "└── " } else { "├── " });
+        line.push_str(&record.name);
+        lines.push(line);
+        ancestors_last.push(last);
+
+        if record.kind == "dir" {
+            dirs += 1;
+        } else {
+            files += 1;
+        }
+    }
+    lines.push(format!("\n{} directories, {} files", dirs, files));
+    lines.join("\n")
+}
+
+#[derive(Default)]
+struct Usage {
+    size: AtomicU64,
+    files: AtomicU64,
+}
+
+// ルート直下の子ごとに中身のサイズを合計する。大きい木でも速いように並列で歩く
+pub fn du(root: &Path, options: &WalkOptions) -> Result<Vec<DuRecord>, JweError> {
+    check_root(root)?;
+
+    // 子の一覧も同じ除外ルールで取る
+    let children: HashMap<PathBuf, (String, Usage)> = walker(
+        root,
+        &WalkOptions {
+            depth: Some(1),
+            ignore: options.ignore.clone(),
+            gitignore: options.gitignore,
+            all: options.all,
+        },
+    )?
+    .build()
+    .flatten()
+    .filter(|entry| entry.depth() == 1)
+    .map(|entry| {
+        let kind = entry
+            .metadata()
+            .map(|metadata| file_controller::kind(&metadata))
+            .unwrap_or("other");
+        (entry.into_path(), (kind.to_string(), Usage::default()))
+    })
+    .collect();
+
+    let skipped = AtomicU64::new(0);
+    walker(root, options)?.build_parallel().run(|| {
+        Box::new(|entry| {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(_) => {
+                    skipped.fetch_add(1, Ordering::Relaxed);
+                    return WalkState::Continue;
+                }
+            };
+            if !entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+            {
+                return WalkState::Continue;
+            }
+            let child = entry
+                .path()
+                .strip_prefix(root)
+                .ok()
+                .and_then(|relative| relative.components().next())
+                .map(|component| root.join(component));
+            if let Some((_, usage)) = child.and_then(|child| children.get(&child)) {
+                let size = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+                usage.size.fetch_add(size, Ordering::Relaxed);
+                usage.files.fetch_add(1, Ordering::Relaxed);
+            }
+            WalkState::Continue
+        })
+    });
+    log_skipped(skipped.into_inner());
+
+    let total: u64 = children
+        .values()
+        .map(|(_, usage)| usage.size.load(Ordering::Relaxed))
+        .sum();
+    let mut records: Vec<DuRecord> = children
+        .into_iter()
+        .map(|(path, (kind, usage))| {
+            let size = usage.size.into_inner();
+            DuRecord {
+                name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                path: path.display().to_string(),
+                kind,
+                size,
+                files: usage.files.into_inner(),
+                percent: units::percent(size, total),
+            }
+        })
+        .collect();
+    records.sort_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(&b.name)));
+    Ok(records)
+}
+
+// records は表示する上位の分、all_records は合計を出すための全件
+pub fn du_table(records: &[DuRecord], all_records: &[DuRecord]) -> String {
+    let mut builder = Builder::default();
+    builder.push_record(["Size", "%", "Files", "Type", "Name"]);
+    for record in records {
+        builder.push_record([
+            units::format_bytes(record.size),
+            format!("{:.1}", record.percent),
+            record.files.to_string(),
+            record.kind.clone(),
+            record.name.clone(),
+        ]);
+    }
+    let mut table = builder.build();
+    table.with(Style::ascii_rounded());
+
+    format!(
+        "{}\nTotal {} in {} files",
+        table,
+        units::format_bytes(all_records.iter().map(|record| record.size).sum()),
+        all_records.iter().map(|record| record.files).sum::<u64>()
+    )
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use once_cell::sync::OnceCell;
+
+use super::logger_control;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnitMode {
+    // KiB, MiB ... (1024 単位)
+    #[default]
+    Binary,
+    // kB, MB ... (1000 単位)
+    Si,
+    // バイト数をそのまま出す
+    Raw,
+}
+
+const BINARY_UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+const SI_UNITS: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+
+static MODE: OnceCell<UnitMode> = OnceCell::new();
+
+// --si / --raw から main で 1 回だけ設定する。設定しなければ Binary
+pub fn set_mode(mode: UnitMode) {
+    if MODE.set(mode).is_err() {
+        logger_control::log(
+            "Unit mode already initialized",
+            logger_control::LogLevel::WARNING,
+        );
+    }
+}
+
+pub fn mode() -> UnitMode {
+    MODE.get().copied().unwrap_or_default()
+}
+
+// 一番大きい単位で 1 以上になるように自動で桁を合わせる
+fn scale(value: f64, suffix: &str) -> String {
+    let (base, units) = match mode() {
+        UnitMode::Raw => return format!("{:.0} B{}", value, suffix),
+        UnitMode::Binary => (1024.0, BINARY_UNITS),
+        UnitMode::Si => (1000.0, SI_UNITS),
+    };
+
+    let mut value = value;
+    let mut unit = 0;
+    while value.abs() >= base && unit < units.len() - 1 {
+        value /= base;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{:.0} {}{}", value, units[unit], suffix)
+    } else {
+        format!("{:.1} {}{}", value, units[unit], suffix)
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    scale(bytes as f64, "")
+}
+
+pub fn format_rate(bytes_per_sec: f64) -> String {
+    scale(bytes_per_sec, "/s")
+}
+
+// total が 0 の時は 0%
+pub fn percent(used: u64, total: u64) -> f64 {
+    if total > 0 {
+        used as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+// 512 / 10K / 1.5MiB / 2GB の形式。K, M, G, T と KiB などは 1024 単位、KB などは 1000 単位
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("expected a size like 512, 10K or 1.5GiB, got {}", value))?;
+
+    let multiplier: f64 = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "k" | "kib" => 1024.0,
+        "m" | "mib" => 1024.0_f64.powi(2),
+        "g" | "gib" => 1024.0_f64.powi(3),
+        "t" | "tib" => 1024.0_f64.powi(4),
+        "kb" => 1e3,
+        "mb" => 1e6,
+        "gb" => 1e9,
+        "tb" => 1e12,
+        _ => {
+            return Err(format!(
+                "unknown size unit in {} (use K, M, G, T, KiB ... or KB ...)",
+                value
+            ))
+        }
+    };
+    Ok((number * multiplier).round() as u64)
+}
+
+// 30s / 15m / 1h / 7d の形式
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("expected a duration like 30m, 1h or 7d, got {}", value))?;
+
+    let secs = match unit {
+        "s" => number,
+        "m" => number.saturating_mul(60),
+        "h" => number.saturating_mul(60 * 60),
+        "d" => number.saturating_mul(60 * 60 * 24),
+        _ => {
+            return Err(format!(
+                "unknown duration unit in {} (use s, m, h or d)",
+                value
+            ))
+        }
+    };
+    Ok(Duration::from_secs(secs))
+}
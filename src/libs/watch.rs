@@ -0,0 +1,244 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sysinfo::{System, MINIMUM_CPU_UPDATE_INTERVAL};
+use tabled::{builder::Builder, settings::Style};
+
+use super::error::JweError;
+use super::logger_control;
+use super::output::{self, OutputFormat};
+use super::records::SummaryRecord;
+use super::units;
+
+static STOP: AtomicBool = AtomicBool::new(false);
+
+pub struct WatchOptions {
+    pub interval: Duration,
+    pub count: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct Stats {
+    min: Option<f64>,
+    max: Option<f64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Stats {
+    pub fn add(&mut self, value: f64) {
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+        self.sum += value;
+        self.count += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min.unwrap_or_default()
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max.unwrap_or_default()
+    }
+
+    pub fn avg(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum / self.count as f64
+        }
+    }
+
+    pub fn summary(&self, metric: &str, unit: &str) -> SummaryRecord {
+        SummaryRecord {
+            metric: metric.to_string(),
+            unit: unit.to_string(),
+            min: self.min(),
+            avg: self.avg(),
+            max: self.max(),
+            samples: self.count,
+        }
+    }
+}
+
+// Ctrl+C で止めた時も最後のまとめを出せるようにフラグだけ立てる
+fn install_ctrlc_handler() {
+    STOP.store(false, Ordering::SeqCst);
+    if let Err(e) = ctrlc::set_handler(|| STOP.store(true, Ordering::SeqCst)) {
+        logger_control::log(
+            &format!("Failed to set Ctrl+C handler: {}", e),
+            logger_control::LogLevel::ERROR,
+        );
+    }
+}
+
+pub fn stopped() -> bool {
+    STOP.load(Ordering::SeqCst)
+}
+
+// 待っている間も Ctrl+C に早く反応するよう短く区切って寝る
+pub fn sleep(duration: Duration) {
+    let until = Instant::now() + duration;
+    while !stopped() {
+        let now = Instant::now();
+        if now >= until {
+            break;
+        }
+        thread::sleep((until - now).min(Duration::from_millis(100)));
+    }
+}
+
+// count が無ければ Ctrl+C まで続ける。取ったサンプル数を返す
+pub fn run(
+    options: &WatchOptions,
+    mut sample: impl FnMut(u64) -> Result<(), JweError>,
+) -> Result<u64, JweError> {
+    install_ctrlc_handler();
+
+    let mut taken = 0;
+    while !stopped() && options.count.is_none_or(|count| taken < count) {
+        if taken > 0 {
+            sleep(options.interval);
+            if stopped() {
+                break;
+            }
+        }
+        taken += 1;
+        sample(taken)?;
+    }
+    Ok(taken)
+}
+
+pub fn format_value(value: f64, unit: &str) -> String {
+    match unit {
+        "bytes" => units::format_bytes(value.round() as u64),
+        "bytes/s" => units::format_rate(value),
+        _ => format!("{:.1}%", value),
+    }
+}
+
+// table の時はサンプルを stdout に流し、それ以外はまとめだけを stdout に出す
+pub fn emit(format: OutputFormat, line: String) {
+    if format == OutputFormat::Table {
+        println!("{}", line);
+    } else {
+        eprintln!("{}", line);
+    }
+}
+
+pub fn print_summary(format: OutputFormat, records: &[SummaryRecord]) -> Result<(), JweError> {
+    output::print(format, records, || {
+        let mut builder = Builder::default();
+        builder.push_record(["Metric", "Min", "Avg", "Max", "Samples"]);
+        for record in records {
+            builder.push_record([
+                record.metric.clone(),
+                format_value(record.min, &record.unit),
+                format_value(record.avg, &record.unit),
+                format_value(record.max, &record.unit),
+                record.samples.to_string(),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::ascii_rounded());
+        format!("\n{}", table)
+    })
+}
+
+pub fn timestamp() -> String {
+    chrono::Local::now().format("%H:%M:%S").to_string()
+}
+
+pub fn cpu(format: OutputFormat, options: &WatchOptions, per_core: bool) -> Result<(), JweError> {
+    let mut sys = System::new();
+    // CPU 使用率は 2 回の更新の差分なので、最初に 1 回更新しておく
+    sys.refresh_cpu_usage();
+    thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+
+    let mut total = Stats::default();
+    let mut cores: Vec<Stats> = Vec::new();
+
+    let samples = run(options, |_| {
+        sys.refresh_cpu_usage();
+        let usage = sys.global_cpu_usage();
+        total.add(usage as f64);
+
+        let mut line = format!("{}  total {:5.1}%", timestamp(), usage);
+        cores.resize_with(sys.cpus().len(), Stats::default);
+        for (stats, cpu) in cores.iter_mut().zip(sys.cpus()) {
+            stats.add(cpu.cpu_usage() as f64);
+            if per_core {
+                line.push_str(&format!("  {} {:5.1}%", cpu.name(), cpu.cpu_usage()));
+            }
+        }
+        emit(format, line);
+        Ok(())
+    })?;
+
+    let mut summary = vec![total.summary("total", "%")];
+    if per_core {
+        summary.extend(
+            cores
+                .iter()
+                .zip(sys.cpus())
+                .map(|(stats, cpu)| stats.summary(cpu.name(), "%")),
+        );
+    }
+    print_summary(format, &summary)?;
+    logger_control::log(
+        &format!("CPU watch finished after {} samples", samples),
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
+
+pub fn memory(format: OutputFormat, options: &WatchOptions) -> Result<(), JweError> {
+    let mut sys = System::new();
+    let mut used = Stats::default();
+    let mut used_percent = Stats::default();
+    let mut available = Stats::default();
+    let mut free = Stats::default();
+
+    let samples = run(options, |_| {
+        sys.refresh_memory();
+        let percent = units::percent(sys.used_memory(), sys.total_memory());
+
+        used.add(sys.used_memory() as f64);
+        used_percent.add(percent);
+        available.add(sys.available_memory() as f64);
+        free.add(sys.free_memory() as f64);
+
+        emit(
+            format,
+            format!(
+                "{}  used {} ({:.1}%)  available {}  free {}",
+                timestamp(),
+                units::format_bytes(sys.used_memory()),
+                percent,
+                units::format_bytes(sys.available_memory()),
+                units::format_bytes(sys.free_memory())
+            ),
+        );
+        Ok(())
+    })?;
+
+    print_summary(
+        format,
+        &[
+            used.summary("used", "bytes"),
+            used_percent.summary("used_percent", "%"),
+            available.summary("available", "bytes"),
+            free.summary("free", "bytes"),
+        ],
+    )?;
+    logger_control::log(
+        &format!("Memory watch finished after {} samples", samples),
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
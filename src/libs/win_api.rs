@@ -25,17 +25,74 @@ use winapi::um::winnt::{
 };
 use windows::Win32::System::Com::CoTaskMemFree;
 
+use crate::libs::data_controller::{LaunchMode, LauncherEntry};
+use crate::libs::error::JweError;
 use crate::libs::logger_control;
+use crate::libs::platform::Platform;
 use crate::VERISON;
 
+pub struct WindowsPlatform;
+
+impl Platform for WindowsPlatform {
+    fn name(&self) -> &'static str {
+        "windows"
+    }
+
+    fn get_local_appdata(&self) -> Option<PathBuf> {
+        get_local_appdata()
+    }
+
+    fn get_local_low(&self) -> Option<PathBuf> {
+        get_local_low()
+    }
+
+    fn get_roaming(&self) -> Option<PathBuf> {
+        get_roaming()
+    }
+
+    fn get_appdata(&self) -> Option<PathBuf> {
+        get_appdata()
+    }
+
+    fn open_explorer(&self, path: &Path) -> Result<(), JweError> {
+        open_explorer(path)
+    }
+
+    fn open_task_manager(&self) -> Result<(), JweError> {
+        open_task_manager()
+    }
+
+    fn open_environment_variables_window(&self) -> Result<(), JweError> {
+        open_environment_variables_window()
+    }
+
+    fn refresh_explorer(&self) -> Result<(), JweError> {
+        refresh_exprorer()
+    }
+
+    fn get_all_user_sids(&self) -> Result<Vec<String>, JweError> {
+        get_all_user_sids()
+    }
+
+    fn terminate_pid(&self, pid: u32) -> Result<(), JweError> {
+        taskkill(pid, false)
+    }
+
+    fn kill_pid(&self, pid: u32) -> Result<(), JweError> {
+        taskkill(pid, true)
+    }
+
+    fn elevated_command(&self, entry: &LauncherEntry) -> Result<Command, JweError> {
+        Ok(elevated_command(entry))
+    }
+}
+
 #[link(name = "win_sys_api", kind = "static")]
 extern "C" {
     fn restart_explorer();
-    fn list_all_pids() -> *mut DWORD;
-    fn free_pids(pids: *mut DWORD);
 }
 
-pub fn get_all_user_sids() -> Result<Vec<String>, Box<dyn std::error::Error>> {
+pub fn get_all_user_sids() -> Result<Vec<String>, JweError> {
     unsafe {
         let mut user_info: *mut USER_INFO_0 = ptr::null_mut();
         let mut entries_read: DWORD = 0;
@@ -55,7 +112,7 @@ pub fn get_all_user_sids() -> Result<Vec<String>, Box<dyn std::error::Error>> {
         );
 
         if status != NERR_Success {
-            return Err("Failed to enumerate users".into());
+            return Err(JweError::Platform("Failed to enumerate users".to_string()));
         }
 
         let mut sids = Vec::new();
@@ -219,28 +276,23 @@ pub fn get_appdata() -> Option<PathBuf> {
     }
 }
 
-pub fn get_app_folder() -> Option<PathBuf> {
-    let local_data = dirs::data_local_dir().expect("Failed to get local app data directory");
-
-    let launcher_loc = local_data.join("johma_windows_enhanced");
-
-    return Some(launcher_loc);
-}
-
-pub fn open_explorer<P>(path: P) -> Result<(), Box<dyn std::error::Error>>
+pub fn open_explorer<P>(path: P) -> Result<(), JweError>
 where
     P: AsRef<Path>,
 {
     let path = path.as_ref();
     let path = PathBuf::from(path);
-    let path = path.canonicalize().unwrap();
+    let path = path.canonicalize().map_err(|e| JweError::File(path, e))?;
 
     if let Err(e) = open::that(&path) {
-        eprintln!("Failed to open explorer: {}", e);
         logger_control::log(
             &format!("Failed to open explorer: {}", e),
             logger_control::LogLevel::CRITICAL,
         );
+        return Err(JweError::Platform(format!(
+            "Failed to open explorer: {}",
+            e
+        )));
     }
     logger_control::log(
         &format!("Opened explorer at: {}", path.display()),
@@ -249,19 +301,22 @@ where
     Ok(())
 }
 
-pub fn open_task_manager() -> Result<(), Box<dyn std::error::Error>> {
+pub fn open_task_manager() -> Result<(), JweError> {
     if let Err(e) = open::that("taskmgr") {
-        eprintln!("Failed to open task manager: {}", e);
         logger_control::log(
             &format!("Failed to open task manager: {}", e),
             logger_control::LogLevel::CRITICAL,
         );
+        return Err(JweError::Platform(format!(
+            "Failed to open task manager: {}",
+            e
+        )));
     }
     logger_control::log("Opened task manager", logger_control::LogLevel::INFO);
     Ok(())
 }
 
-pub fn refresh_exprorer() -> Result<(), Box<dyn std::error::Error>> {
+pub fn refresh_exprorer() -> Result<(), JweError> {
     unsafe {
         restart_explorer();
     }
@@ -269,18 +324,7 @@ pub fn refresh_exprorer() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-pub fn show_all_pid() {
-    unsafe {
-        let pids = list_all_pids();
-        if pids.is_null() {
-            eprintln!("Failed to get PIDs: list_all_pids returned NULL");
-            return;
-        }
-        free_pids(pids);
-    }
-}
-
-pub fn open_environment_variables_window() -> Result<(), Box<dyn std::error::Error>> {
+pub fn open_environment_variables_window() -> Result<(), JweError> {
     if cfg!(target_os = "windows") {
         let result = Command::new("SystemPropertiesAdvanced.exe")
             .arg("/c")
@@ -295,68 +339,83 @@ pub fn open_environment_variables_window() -> Result<(), Box<dyn std::error::Err
                 Ok(())
             }
             Err(e) => {
-                eprintln!("Failed to open environment variables window: {}", e);
                 logger_control::log(
                     &format!("Failed to open environment variables window: {}", e),
                     logger_control::LogLevel::CRITICAL,
                 );
-                Err(e.into())
+                Err(JweError::Io(e))
             }
         }
     } else {
-        eprintln!("Failed to open environment variables window: Unsupported OS");
         logger_control::log(
             "Failed to open environment variables window: Unsupported OS",
             logger_control::LogLevel::CRITICAL,
         );
-        Err("Unsupported OS".into())
+        Err(JweError::Platform("Unsupported OS".to_string()))
     }
 }
 
-pub fn kill_pid(pid: u32) -> Result<(), Box<dyn std::error::Error>> {
-    let result = Command::new("taskkill")
-        .arg("/F")
-        .arg("/PID")
-        .arg(pid.to_string())
-        .spawn();
+// /F を付けないとウィンドウに終了要求を送るだけになる
+pub fn taskkill(pid: u32, force: bool) -> Result<(), JweError> {
+    let mut command = Command::new("taskkill");
+    if force {
+        command.arg("/F");
+    }
+    let result = command.arg("/PID").arg(pid.to_string()).output();
 
     match result {
-        Ok(_) => {
+        Ok(output) if output.status.success() => {
             logger_control::log(
-                &format!("Killed PID: {}", pid),
+                &format!("taskkill PID: {} (force: {})", pid, force),
                 logger_control::LogLevel::INFO,
             );
             Ok(())
         }
+        Ok(output) => {
+            let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            logger_control::log(
+                &format!("Failed to kill PID: {} {}", pid, message),
+                logger_control::LogLevel::CRITICAL,
+            );
+            Err(JweError::Platform(format!(
+                "taskkill failed for PID {}: {}",
+                pid, message
+            )))
+        }
         Err(e) => {
-            eprintln!("Failed to kill PID: {}", e);
             logger_control::log(
                 &format!("Failed to kill PID: {}", e),
                 logger_control::LogLevel::CRITICAL,
             );
-            Err(e.into())
+            Err(JweError::Io(e))
         }
     }
 }
 
-pub fn run_launcher(path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let result = Command::new(path).spawn();
+fn quote_powershell(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
 
-    match result {
-        Ok(_) => {
-            logger_control::log(
-                &format!("Ran launcher: {}", path),
-                logger_control::LogLevel::INFO,
-            );
-            Ok(())
-        }
-        Err(e) => {
-            eprintln!("Failed to run launcher: {}", e);
-            logger_control::log(
-                &format!("Failed to run launcher: {}", e),
-                logger_control::LogLevel::CRITICAL,
-            );
-            Err(e.into())
-        }
+// UAC の確認を出すために PowerShell の Start-Process -Verb RunAs 経由で起動する
+// 環境変数は昇格したプロセスには引き継がれない
+pub fn elevated_command(entry: &LauncherEntry) -> Command {
+    let mut script = format!(
+        "Start-Process -FilePath {} -Verb RunAs",
+        quote_powershell(&entry.program)
+    );
+    if !entry.args.is_empty() {
+        let args: Vec<String> = entry.args.iter().map(|arg| quote_powershell(arg)).collect();
+        script.push_str(&format!(" -ArgumentList {}", args.join(",")));
     }
+    if let Some(cwd) = &entry.cwd {
+        script.push_str(&format!(" -WorkingDirectory {}", quote_powershell(cwd)));
+    }
+    if entry.mode == LaunchMode::Wait {
+        // 終了コードを powershell の終了コードとして返す
+        script = format!("$p = {} -Wait -PassThru; exit $p.ExitCode", script);
+    }
+
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-Command", &script]);
+    command
 }
@@ -83,59 +83,3 @@ void debug_print(const char *msg)
         WriteConsoleA(hConsole, "\n", 1, &written, NULL);
     }
 }
-
-__declspec(dllexport) void *list_all_pids()
-{
-    HANDLE snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
-    if (snapshot == INVALID_HANDLE_VALUE)
-    {
-        return NULL;
-    }
-
-    PROCESSENTRY32 pe32;
-    pe32.dwSize = sizeof(PROCESSENTRY32);
-
-    if (!Process32First(snapshot, &pe32))
-    {
-        CloseHandle(snapshot);
-        return NULL;
-    }
-
-    size_t pid_capacity = 10;
-    size_t pid_count = 0;
-    DWORD *pids = (DWORD *)malloc(pid_capacity * sizeof(DWORD));
-    if (pids == NULL)
-    {
-        CloseHandle(snapshot);
-        return NULL;
-    }
-
-    do
-    {
-        printf("PID: %d NAME: %s \n", pe32.th32ProcessID, pe32.szExeFile);
-        fflush(stdout);
-        Sleep(1);
-        if (pid_count >= pid_capacity)
-        {
-            pid_capacity *= 2;
-            DWORD *temp = (DWORD *)realloc(pids, pid_capacity * sizeof(DWORD));
-            if (temp == NULL)
-            {
-                free(pids);
-                CloseHandle(snapshot);
-                return NULL;
-            }
-            pids = temp;
-        }
-        pids[pid_count++] = pe32.th32ProcessID;
-    } while (Process32Next(snapshot, &pe32));
-
-    pids[pid_count] = 0;
-    CloseHandle(snapshot);
-    return pids;
-}
-
-__declspec(dllexport) void free_pids(DWORD *pids)
-{
-    free(pids);
-}
\ No newline at end of file
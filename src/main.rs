@@ -1,17 +1,36 @@
 mod libs;
-use clap::{ArgAction, Parser, Subcommand};
-use libs::{data_controller, logger_control, win_api};
-use std::{
-    collections::HashMap,
-    env::consts::OS,
-    fs::{self, File},
-    io::{self, Write},
+use clap::{ArgGroup, Parser, Subcommand};
+use libs::data_controller::{GroupItem, LaunchMode, LauncherEntry, LauncherGroup};
+use libs::error::JweError;
+use libs::file_controller::{self, FileSort, ListOptions};
+use libs::find::{self, FileKind, FindOptions};
+use libs::launcher::{self, GroupItemSpec};
+use libs::memory_controller::{self, Thresholds};
+use libs::migration::{self, MigrationReport};
+use libs::output::{self, OutputFormat};
+use libs::platform::{self, Platform};
+use libs::process_controller::{KillOptions, ProcessFilter, ProcessSort, ProcessTarget};
+use libs::records::{
+    CpuRecord, FavoriteRecord, GroupItemRecord, LauncherRecord, MemoryRecord, MemoryReport,
+    SidRecord,
+};
+use libs::sensors_controller::SensorAlert;
+use libs::top::{self, TopOptions};
+use libs::trash::{self, Conflict, RemoveOptions};
+use libs::tree::{self, WalkOptions};
+use libs::units::{self, UnitMode};
+use libs::watch::{self, WatchOptions};
+use libs::{
+    config_controller, data_controller, disk_controller, logger_control, monitor,
+    network_controller, process_controller, prompt, sensors_controller, system_controller,
 };
-use sysinfo::{Components, Disks, Networks, System};
-use tabled::{
-    builder::Builder,
-    settings::{object::Columns, object::Rows, Alignment, Modify, Padding, Style, Width},
+use std::{
+    path::{Path, PathBuf},
+    process, thread,
+    time::Duration,
 };
+use sysinfo::{System, Users, MINIMUM_CPU_UPDATE_INTERVAL};
+use tabled::{builder::Builder, settings::Style};
 
 // プリプロセッサー
 const VERISON: &str = env!("CARGO_PKG_VERSION");
@@ -19,11 +38,36 @@ const VERISON: &str = env!("CARGO_PKG_VERSION");
 #[derive(Parser)]
 #[command(version, long_about = None)]
 struct Args {
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "Directory for settings and data files (default: $JWE_DATA_DIR or local app data)"
+    )]
+    data_dir: Option<PathBuf>,
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = OutputFormat::Table,
+        help = "Output format for query commands"
+    )]
+    output: OutputFormat,
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "raw",
+        help = "Show sizes in SI units (kB, MB, GB) instead of KiB, MiB, GiB"
+    )]
+    si: bool,
+    #[arg(long, global = true, help = "Show sizes as raw byte counts")]
+    raw: bool,
     #[command(subcommand)]
     command: Option<Commands>,
 }
 #[derive(Subcommand)]
 enum Commands {
+    #[allow(clippy::upper_case_acronyms)]
     #[command(about = "cpu information", long_about = "cpu information")]
     CPU {
         #[command(subcommand)]
@@ -35,12 +79,146 @@ enum Commands {
         action: Option<MemShowCommands>,
     },
     #[command(
-        about = "List files in the current directory",
-        long_about = "List files in the current directory"
+        about = "List files in a directory",
+        long_about = "List files in a directory (the current directory by default) as a grid, or with size, modified time, permissions and type in long mode"
     )]
     Ls {
-        #[arg(long)]
+        #[arg(value_name = "PATH", help = "Directory or file to list")]
+        path: Option<PathBuf>,
+        #[arg(long, help = "Show full paths")]
         action: bool,
+        #[arg(
+            short,
+            long,
+            help = "Long listing with size, modified time, permissions and type"
+        )]
+        long: bool,
+        #[arg(short, long, help = "Include hidden files")]
+        all: bool,
+        #[arg(long, value_enum, default_value_t = FileSort::Name, help = "Sort order")]
+        sort: FileSort,
+        #[arg(short, long, help = "Reverse the sort order")]
+        reverse: bool,
+        #[arg(long, help = "List directories before files")]
+        dirs_first: bool,
+    },
+    #[command(
+        about = "Show a directory tree",
+        long_about = "Show a directory tree. Entries matched by .gitignore, .ignore or --ignore globs are skipped"
+    )]
+    Tree {
+        #[arg(value_name = "PATH", help = "Directory to show")]
+        path: Option<PathBuf>,
+        #[arg(short = 'L', long, value_name = "N", help = "Maximum depth")]
+        depth: Option<usize>,
+        #[arg(
+            short = 'I',
+            long = "ignore",
+            value_name = "GLOB",
+            help = "Skip entries matching this glob (repeatable)"
+        )]
+        ignore: Vec<String>,
+        #[arg(long, help = "Do not read .gitignore and .ignore files")]
+        no_gitignore: bool,
+        #[arg(short, long, help = "Include hidden files")]
+        all: bool,
+    },
+    #[command(
+        about = "Show directory sizes",
+        long_about = "Show the recursive size of each entry in a directory, biggest first. Entries matched by .gitignore, .ignore or --ignore globs are skipped"
+    )]
+    Du {
+        #[arg(value_name = "PATH", help = "Directory to measure")]
+        path: Option<PathBuf>,
+        #[arg(
+            short,
+            long,
+            value_name = "N",
+            default_value_t = 20,
+            help = "Number of entries to show"
+        )]
+        top: usize,
+        #[arg(
+            short = 'I',
+            long = "ignore",
+            value_name = "GLOB",
+            help = "Skip entries matching this glob (repeatable)"
+        )]
+        ignore: Vec<String>,
+        #[arg(long, help = "Do not read .gitignore and .ignore files")]
+        no_gitignore: bool,
+        #[arg(short, long, help = "Include hidden files")]
+        all: bool,
+    },
+    #[command(
+        about = "Find files",
+        long_about = "Find files whose name matches a pattern (a substring, a glob such as *.rs, or a regex with --regex), optionally filtered by type, size, modified time and content. Entries matched by .gitignore, .ignore or --ignore globs are skipped"
+    )]
+    Find {
+        #[arg(help = "Name substring, glob, or regex with --regex (case insensitive)")]
+        pattern: String,
+        #[arg(value_name = "PATH", help = "Directory to search")]
+        path: Option<PathBuf>,
+        #[arg(long, help = "Treat the pattern as a regex")]
+        regex: bool,
+        #[arg(short = 't', long = "type", value_enum, help = "Only this type")]
+        kind: Option<FileKind>,
+        #[arg(
+            long,
+            value_name = "SIZE",
+            value_parser = units::parse_size,
+            help = "Minimum size (e.g. 512, 10K, 1.5MiB, 2GB)"
+        )]
+        min_size: Option<u64>,
+        #[arg(
+            long,
+            value_name = "SIZE",
+            value_parser = units::parse_size,
+            help = "Maximum size (e.g. 512, 10K, 1.5MiB, 2GB)"
+        )]
+        max_size: Option<u64>,
+        #[arg(
+            long,
+            value_name = "DURATION",
+            value_parser = units::parse_duration,
+            help = "Modified within this duration (e.g. 30m, 1h, 7d)"
+        )]
+        newer: Option<Duration>,
+        #[arg(
+            long,
+            value_name = "DURATION",
+            value_parser = units::parse_duration,
+            help = "Modified longer ago than this duration (e.g. 30m, 1h, 7d)"
+        )]
+        older: Option<Duration>,
+        #[arg(
+            short,
+            long,
+            value_name = "REGEX",
+            help = "Only files whose content matches this regex"
+        )]
+        grep: Option<String>,
+        #[arg(short, long, requires = "grep", help = "Case insensitive --grep")]
+        ignore_case: bool,
+        #[arg(short = 'L', long, value_name = "N", help = "Maximum depth")]
+        depth: Option<usize>,
+        #[arg(
+            short = 'I',
+            long = "ignore",
+            value_name = "GLOB",
+            help = "Skip entries matching this glob (repeatable)"
+        )]
+        ignore: Vec<String>,
+        #[arg(long, help = "Do not read .gitignore and .ignore files")]
+        no_gitignore: bool,
+        #[arg(short, long, help = "Include hidden files")]
+        all: bool,
+        #[arg(
+            short,
+            long,
+            help = "Long listing with size, modified time, permissions and type"
+        )]
+        long: bool,
     },
     #[command(about = "Browser information", long_about = "Browser information")]
     Browser {
@@ -55,10 +233,36 @@ enum Commands {
         #[command(subcommand)]
         action: Option<OpenCommands>,
     },
-    #[command(about = "Remove a file", long_about = "Remove a file")]
+    #[command(
+        about = "Remove files and directories",
+        long_about = "Move files and directories into the jwe trash under the data directory so they can be restored later. Glob patterns are expanded by jwe. Asks for confirmation when many files would be removed"
+    )]
     Rm {
-        #[arg(short, long, help = "Remove a file")]
-        remove: Option<String>,
+        #[arg(
+            required = true,
+            help = "Files, directories or glob patterns (e.g. *.log)"
+        )]
+        paths: Vec<String>,
+        #[arg(short, long, help = "Remove directories and their contents")]
+        recursive: bool,
+        #[arg(long, help = "Delete permanently instead of moving to the trash")]
+        permanent: bool,
+        #[arg(
+            short = 'n',
+            long,
+            help = "Show what would be removed without removing it"
+        )]
+        dry_run: bool,
+        #[arg(short, long, help = "Do not ask for confirmation")]
+        yes: bool,
+    },
+    #[command(
+        about = "Manage the jwe trash",
+        long_about = "List, restore and purge items removed with jwe rm"
+    )]
+    Trash {
+        #[command(subcommand)]
+        action: Option<TrashCommands>,
     },
     #[command(about = "Update the program", long_about = "Update the program")]
     Update {
@@ -78,11 +282,57 @@ enum Commands {
         #[command(subcommand)]
         action: Option<ProcCommands>,
     },
+    #[command(about = "Disk information", long_about = "Disk information")]
+    Disk {
+        #[command(subcommand)]
+        action: Option<DiskCommands>,
+    },
+    #[command(about = "Network information", long_about = "Network information")]
+    Net {
+        #[command(subcommand)]
+        action: Option<NetCommands>,
+    },
+    #[command(
+        about = "Temperature sensors",
+        long_about = "Show hardware temperature sensors with current, max and critical temperatures. With --warn-above or --warn-critical the command exits with code 7 when a sensor is too hot"
+    )]
+    Sensors {
+        #[arg(
+            long,
+            value_name = "CELSIUS",
+            help = "Exit with an error when a sensor is at or above this temperature"
+        )]
+        warn_above: Option<f32>,
+        #[arg(
+            long,
+            help = "Exit with an error when a sensor reaches its critical temperature"
+        )]
+        warn_critical: bool,
+    },
+    #[command(
+        about = "System summary",
+        long_about = "Show OS, kernel, hostname, uptime, boot time, CPU, memory, swap, disks, networks and load average in one report"
+    )]
+    Sysinfo,
     #[command(about = "Launcher Control", long_about = "Launcher Control")]
     Lc {
         #[command(subcommand)]
         action: Option<LcCommands>,
     },
+    #[command(
+        about = "Resource history",
+        long_about = "Record CPU, memory, disk and network usage and report on it"
+    )]
+    Monitor {
+        #[command(subcommand)]
+        action: Option<MonitorCommands>,
+    },
+
+    #[command(about = "Settings control", long_about = "Settings control")]
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigCommands>,
+    },
 
     #[command(about = "Show version", long_about = "Show version")]
     Version,
@@ -96,8 +346,29 @@ enum CPUCommands {
         all: bool,
         #[arg(short, long, help = "Show CPU usage information")]
         usage: bool,
-        #[arg(short, long, help = "Show CPU temperature information")]
+        #[arg(short, long, help = "Show CPU frequency information (MHz)")]
         frequency: bool,
+        #[arg(short, long, help = "Show CPU temperature information")]
+        temperature: bool,
+    },
+    #[command(
+        about = "Watch CPU usage",
+        long_about = "Watch CPU usage and show min/avg/max at the end. Runs until Ctrl+C when --count is not given"
+    )]
+    Watch {
+        #[arg(
+            short,
+            long,
+            value_name = "SECS",
+            default_value_t = 1,
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Sampling interval"
+        )]
+        interval: u64,
+        #[arg(short, long, help = "Number of samples")]
+        count: Option<u64>,
+        #[arg(short, long, help = "Also show each core")]
+        per_core: bool,
     },
 }
 
@@ -116,6 +387,55 @@ enum MemShowCommands {
         used: bool,
         #[arg(short = 'v', long, help = "Show available memory information")]
         available: bool,
+        #[arg(short, long, help = "Show swap total, used and free")]
+        swap: bool,
+        #[arg(short, long, help = "Show memory and swap usage percentages")]
+        percent: bool,
+        #[arg(
+            long,
+            help = "Show memory pressure (ok, warn or critical) based on available memory"
+        )]
+        pressure: bool,
+        #[arg(
+            long,
+            value_name = "PERCENT",
+            value_parser = config_controller::parse_percent,
+            requires = "pressure",
+            help = "Pressure warn threshold (default: memory_warn_percent setting)"
+        )]
+        warn_at: Option<f64>,
+        #[arg(
+            long,
+            value_name = "PERCENT",
+            value_parser = config_controller::parse_percent,
+            requires = "pressure",
+            help = "Pressure critical threshold (default: memory_critical_percent setting)"
+        )]
+        critical_at: Option<f64>,
+        #[arg(
+            short,
+            long,
+            value_name = "N",
+            help = "Show the top N processes by memory"
+        )]
+        top: Option<usize>,
+    },
+    #[command(
+        about = "Watch memory usage",
+        long_about = "Watch memory usage and show min/avg/max at the end. Runs until Ctrl+C when --count is not given"
+    )]
+    Watch {
+        #[arg(
+            short,
+            long,
+            value_name = "SECS",
+            default_value_t = 1,
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Sampling interval"
+        )]
+        interval: u64,
+        #[arg(short, long, help = "Number of samples")]
+        count: Option<u64>,
     },
 }
 
@@ -134,6 +454,12 @@ enum BrowserCommands {
         reset: bool,
         #[arg(long, help = "Set Search Engine")]
         set_search: bool,
+        #[arg(
+            long,
+            value_name = "ENGINE",
+            help = "Set Search Engine without prompting : DuckDuckGo, Google, Bing"
+        )]
+        engine: Option<String>,
     },
     #[command(about = "Search in browser", long_about = "Search in browser")]
     Search {
@@ -142,6 +468,8 @@ enum BrowserCommands {
     },
     #[command(about = "Web Favorite", long_about = "Web Favorite")]
     Fav {
+        #[command(subcommand)]
+        action: Option<FavCommands>,
         #[arg(long, short, help = "Add a favorite URL")]
         add_favorite: bool,
         #[arg(long, short, help = "Remove a favorite URL")]
@@ -153,6 +481,24 @@ enum BrowserCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum FavCommands {
+    #[command(about = "Add a favorite URL", long_about = "Add a favorite URL")]
+    Add {
+        name: Option<String>,
+        url: Option<String>,
+    },
+    #[command(about = "Remove a favorite URL", long_about = "Remove a favorite URL")]
+    Remove { name: Option<String> },
+    #[command(
+        about = "List all favorite URLs",
+        long_about = "List all favorite URLs"
+    )]
+    List,
+    #[command(about = "Open a favorite URL", long_about = "Open a favorite URL")]
+    Open { name: String },
+}
+
 #[derive(Subcommand)]
 
 enum OpenCommands {
@@ -187,56 +533,395 @@ enum OpenCommands {
 
 #[derive(Subcommand)]
 enum ProcCommands {
-    #[command(about = "Show all processes", long_about = "Show all processes")]
+    #[command(
+        about = "Show processes",
+        long_about = "Show processes with parent PID, CPU, memory, user, start time and path"
+    )]
     Show {
-        #[arg(short, long, help = "Show all processes")]
+        #[arg(short, long, help = "Show all processes (default)")]
         all: bool,
+        #[arg(long, help = "Only show processes whose name contains this text")]
+        name: Option<String>,
+        #[arg(long, help = "Only show processes owned by this user")]
+        user: Option<String>,
+        #[arg(long, value_enum, default_value_t = ProcessSort::Pid, help = "Sort order")]
+        sort: ProcessSort,
+        #[arg(long, value_name = "N", help = "Only show the first N processes")]
+        top: Option<usize>,
+        #[arg(long, help = "Show processes as a parent/child tree")]
+        tree: bool,
     },
-    #[command(about = "Kill a process", long_about = "Kill a process")]
+    #[command(
+        about = "Live process monitor",
+        long_about = "Live process monitor. Keys: q quit, c/m/p sort by CPU/memory/PID, / filter by name, Up/Down select, k kill the selected process"
+    )]
+    Top {
+        #[arg(
+            long,
+            value_name = "SECS",
+            default_value_t = 2,
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Refresh interval"
+        )]
+        interval: u64,
+        #[arg(long, value_enum, default_value_t = ProcessSort::Cpu, help = "Sort order")]
+        sort: ProcessSort,
+        #[arg(long, help = "Only show processes whose name contains this text")]
+        name: Option<String>,
+    },
+    #[command(
+        about = "Kill processes",
+        long_about = "Kill processes by PID, name, glob pattern or regex. A graceful terminate is tried first and the process is force killed if it is still running after the timeout",
+        group(ArgGroup::new("process").required(true).args(["target", "pid"]))
+    )]
     Kill {
-        #[arg(short, long, help = "Kill a process")]
-        pid: u32,
+        #[arg(help = "PID, process name or glob pattern (e.g. chrome*)")]
+        target: Option<String>,
+        #[arg(short, long, help = "Kill a process by PID")]
+        pid: Option<u32>,
+        #[arg(long, help = "Treat the target as a regular expression")]
+        regex: bool,
+        #[arg(long, help = "Also kill child processes")]
+        tree: bool,
+        #[arg(long, help = "Skip the graceful terminate and force kill")]
+        force: bool,
+        #[arg(
+            long,
+            value_name = "SECS",
+            default_value_t = 5,
+            help = "Seconds to wait for each step before giving up"
+        )]
+        timeout: u64,
+        #[arg(short, long, help = "Do not ask for confirmation")]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum TrashCommands {
+    #[command(
+        about = "List trash items",
+        long_about = "List trash items with ID, deletion time, size and original path, newest first"
+    )]
+    List,
+    #[command(
+        about = "Restore a trash item",
+        long_about = "Restore a trash item to its original location. The item is given by ID, original file name or original path; a name that matches several items must be given by ID"
+    )]
+    Restore {
+        #[arg(help = "Trash ID, original file name or original path")]
+        target: String,
+        #[arg(
+            long,
+            conflicts_with = "overwrite",
+            help = "Restore as \"name (1)\" when the original path is taken"
+        )]
+        rename: bool,
+        #[arg(long, help = "Replace whatever is at the original path")]
+        overwrite: bool,
+    },
+    #[command(
+        about = "Permanently delete trash items",
+        long_about = "Permanently delete trash items that were removed longer ago than --older-than, or everything with --all",
+        group(ArgGroup::new("selection").required(true).args(["older_than", "all"]))
+    )]
+    Purge {
+        #[arg(
+            long,
+            value_name = "DURATION",
+            value_parser = units::parse_duration,
+            help = "Only purge items deleted longer ago than this (e.g. 12h, 30d)"
+        )]
+        older_than: Option<Duration>,
+        #[arg(long, help = "Purge every item")]
+        all: bool,
+        #[arg(
+            short = 'n',
+            long,
+            help = "Show what would be purged without deleting it"
+        )]
+        dry_run: bool,
+        #[arg(short, long, help = "Do not ask for confirmation")]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DiskCommands {
+    #[command(
+        about = "Show disks",
+        long_about = "Show mounted disks with file system, total, used and free space. With --warn-below the command exits with code 7 when any disk has less free space than the given percent"
+    )]
+    Show {
+        #[arg(
+            long,
+            value_name = "PERCENT",
+            value_parser = config_controller::parse_percent,
+            help = "Exit with an error when free space is below this percent"
+        )]
+        warn_below: Option<f64>,
     },
 }
 
+#[derive(Subcommand)]
+enum NetCommands {
+    #[command(
+        about = "Show network interfaces",
+        long_about = "Show network interfaces with MAC, IP addresses and received/transmitted totals"
+    )]
+    Show,
+    #[command(
+        about = "Watch network throughput",
+        long_about = "Watch per-second throughput per interface and show min/avg/max at the end. Runs until Ctrl+C when --count is not given"
+    )]
+    Watch {
+        #[arg(
+            short,
+            long,
+            value_name = "SECS",
+            default_value_t = 1,
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Sampling interval"
+        )]
+        interval: u64,
+        #[arg(short, long, help = "Number of samples")]
+        count: Option<u64>,
+        #[arg(long, help = "Only watch this interface")]
+        interface: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum MonitorCommands {
+    #[command(
+        about = "Record resource usage",
+        long_about = "Record CPU, memory, disk, network and the busiest processes to the history file in the data directory. Runs until Ctrl+C when --count is not given"
+    )]
+    Record {
+        #[arg(
+            short,
+            long,
+            value_name = "SECS",
+            default_value_t = 60,
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Sampling interval"
+        )]
+        interval: u64,
+        #[arg(short, long, help = "Number of samples")]
+        count: Option<u64>,
+        #[arg(
+            long,
+            value_name = "DURATION",
+            default_value = "30d",
+            value_parser = units::parse_duration,
+            help = "Remove samples older than this from the history (e.g. 12h, 7d)"
+        )]
+        keep: Duration,
+    },
+    #[command(
+        about = "Summarize recorded resource usage",
+        long_about = "Summarize recorded resource usage with peaks, averages and the top processes"
+    )]
+    Report {
+        #[arg(
+            long,
+            value_name = "DURATION",
+            default_value = "24h",
+            value_parser = units::parse_duration,
+            help = "How far back to look (e.g. 30m, 1h, 7d)"
+        )]
+        since: Duration,
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 5,
+            help = "Number of processes to show"
+        )]
+        top: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    #[command(about = "Show a setting", long_about = "Show a setting")]
+    Get { key: String },
+    #[command(about = "Change a setting", long_about = "Change a setting")]
+    Set { key: String, value: String },
+    #[command(
+        about = "Reset a setting to default",
+        long_about = "Reset a setting to default"
+    )]
+    Unset { key: String },
+    #[command(about = "List all settings", long_about = "List all settings")]
+    List,
+    #[command(
+        about = "Show the settings file path",
+        long_about = "Show the settings file path"
+    )]
+    Path,
+    #[command(
+        about = "Open the settings file in $EDITOR",
+        long_about = "Open the settings file in $VISUAL or $EDITOR"
+    )]
+    Edit,
+}
+
 #[derive(Subcommand)]
 enum LcCommands {
     #[command(about = "Show all launchers", long_about = "Show all launchers")]
     Show {
         #[arg(short, long, help = "Show all launchers")]
         all: bool,
+        #[arg(short, long, help = "Show launcher details")]
+        long: bool,
+    },
+    #[command(
+        about = "Add a launcher",
+        long_about = "Add a launcher. Arguments for the program go after --, Example: jwe lc add code code -- C:/work/project"
+    )]
+    Add {
+        name: Option<String>,
+        #[arg(value_name = "PROGRAM")]
+        path: Option<String>,
+        #[arg(
+            last = true,
+            value_name = "ARGS",
+            help = "Arguments passed to the program"
+        )]
+        args: Vec<String>,
+        #[arg(long, help = "Working directory")]
+        cwd: Option<String>,
+        #[arg(
+            long,
+            value_name = "KEY=VALUE",
+            value_parser = launcher::parse_env,
+            help = "Environment variable override (repeatable)"
+        )]
+        env: Vec<(String, String)>,
+        #[arg(long, help = "Description")]
+        description: Option<String>,
+        #[arg(long = "tag", value_name = "TAG", help = "Tag (repeatable)")]
+        tags: Vec<String>,
+        #[arg(long, help = "Wait until the program exits and check the exit code")]
+        wait: bool,
+        #[arg(long, help = "Run as administrator")]
+        elevated: bool,
     },
-    #[command(about = "Add a launcher", long_about = "Add a launcher")]
-    Add,
     #[command(about = "Remove a launcher", long_about = "Remove a launcher")]
-    Remove,
-    #[command(about = "Run a launcher", long_about = "Run a launcher")]
+    Remove { name: Option<String> },
+    #[command(
+        about = "Run a launcher or a launcher group",
+        long_about = "Run a launcher or a launcher group"
+    )]
+    Run { name: String },
+    #[command(
+        about = "Launcher group control",
+        long_about = "Launcher group control"
+    )]
+    Group {
+        #[command(subcommand)]
+        action: Option<LcGroupCommands>,
+    },
+}
+
+#[derive(Subcommand)]
+enum LcGroupCommands {
+    #[command(
+        about = "Add a launcher group",
+        long_about = "Add a launcher group. Launchers are started in the given order. Each launcher can be written as LAUNCHER:DELAY_MS:TIMEOUT_SECS to set its own delay and --wait-started timeout (e.g. db:0:60 web:2000)"
+    )]
+    Add {
+        name: String,
+        #[arg(
+            required = true,
+            value_name = "LAUNCHER[:DELAY_MS[:TIMEOUT_SECS]]",
+            value_parser = launcher::parse_group_item
+        )]
+        launchers: Vec<GroupItemSpec>,
+        #[arg(
+            long,
+            value_name = "MS",
+            default_value_t = 0,
+            help = "Delay before starting each launcher without its own delay"
+        )]
+        delay: u64,
+        #[arg(
+            long,
+            help = "Wait until each program is running before starting the next"
+        )]
+        wait_started: bool,
+        #[arg(
+            long,
+            value_name = "SECS",
+            default_value_t = 30,
+            help = "Timeout for --wait-started for launchers without their own timeout"
+        )]
+        timeout: u64,
+        #[arg(long, help = "Description")]
+        description: Option<String>,
+    },
+    #[command(
+        about = "Remove a launcher group",
+        long_about = "Remove a launcher group"
+    )]
+    Remove { name: String },
+    #[command(
+        about = "Show launcher groups",
+        long_about = "Show launcher groups, or the items of one group"
+    )]
+    Show { name: Option<String> },
+    #[command(about = "Run a launcher group", long_about = "Run a launcher group")]
     Run { name: String },
 }
 fn main() {
     let args = Args::parse();
 
-    libs::logger_control::initialize();
+    if let Some(data_dir) = &args.data_dir {
+        libs::store::set_data_root(data_dir.clone());
+    }
+    units::set_mode(if args.raw {
+        UnitMode::Raw
+    } else if args.si {
+        UnitMode::Si
+    } else {
+        UnitMode::Binary
+    });
+
+    if let Err(e) = logger_control::initialize() {
+        eprintln!("Error: {}", e);
+        process::exit(e.exit_code());
+    }
 
     logger_control::log("Starting program", logger_control::LogLevel::INFO);
 
-    data_controller::init_settings();
-
-    data_controller::null_search_settings();
-
-    match OS {
-        "windows" => {
-            windows_cmd(args);
-            logger_control::log("Program finished", logger_control::LogLevel::INFO);
-        }
+    let platform = platform::current();
+    logger_control::log(
+        &format!("Running on {}", platform.name()),
+        logger_control::LogLevel::INFO,
+    );
+
+    // config path と config edit は壊れた設定ファイルを直すために使うので、読み込みやマイグレーションより先に処理する
+    let result = match &args.command {
+        Some(Commands::Config {
+            action: Some(action @ (ConfigCommands::Path | ConfigCommands::Edit)),
+        }) => config_file_cmd(action),
         _ => {
-            println!("Unsupported OS");
-            logger_control::log("Unsupported OS", logger_control::LogLevel::ERROR);
+            load_data();
+            windows_cmd(args, &platform)
         }
+    };
+
+    logger_control::log("Program finished", logger_control::LogLevel::INFO);
+
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        logger_control::log(&format!("Error: {}", e), logger_control::LogLevel::ERROR);
+        process::exit(e.exit_code());
     }
 }
 
-fn windows_cmd(args: Args) {
+fn windows_cmd(args: Args, platform: &impl Platform) -> Result<(), JweError> {
+    let format = args.output;
     match &args.command {
         // cpu command
         Some(Commands::CPU { action }) => {
@@ -247,42 +932,86 @@ fn windows_cmd(args: Args) {
                     all,
                     usage,
                     frequency,
+                    temperature,
                 }) => {
-                    if *all {
-                        for cpu in sys.cpus() {
-                            println!("{}", cpu.name());
-                            logger_control::log(
-                                &format!("CPU name all called {}", cpu.name()),
-                                logger_control::LogLevel::INFO,
-                            );
-                        }
-                    }
+                    // 使用率は前回の更新との差分なので、少し待ってからもう一度取る
                     if *usage {
-                        for cpu in sys.cpus() {
-                            println!("{}", cpu.cpu_usage());
-                            logger_control::log(
-                                &format!("CPU usage usage called {}", cpu.cpu_usage()),
-                                logger_control::LogLevel::INFO,
+                        thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+                        sys.refresh_cpu_usage();
+                    }
+                    // センサーは CPU 単位では取れないので、CPU 全体の温度を各行に入れる
+                    let cpu_temperature = if *temperature {
+                        sensors_controller::cpu_temperature()
+                    } else {
+                        None
+                    };
+                    let records: Vec<CpuRecord> = sys
+                        .cpus()
+                        .iter()
+                        .map(|cpu| CpuRecord {
+                            name: cpu.name().to_string(),
+                            usage: usage.then(|| cpu.cpu_usage()),
+                            frequency: frequency.then(|| cpu.frequency()),
+                            temperature: cpu_temperature,
+                        })
+                        .collect();
+
+                    output::print(format, &records, || {
+                        let mut lines = Vec::new();
+                        if *all {
+                            lines.extend(records.iter().map(|cpu| cpu.name.clone()));
+                        }
+                        if *usage {
+                            lines.extend(
+                                records
+                                    .iter()
+                                    .filter_map(|cpu| cpu.usage)
+                                    .map(|usage| usage.to_string()),
                             );
                         }
-                    }
-                    if *frequency {
-                        for cpu in sys.cpus() {
-                            println!("{}", cpu.frequency());
-                            logger_control::log(
-                                &format!("CPU frequency frequency called {}", cpu.frequency()),
-                                logger_control::LogLevel::INFO,
+                        if *frequency {
+                            lines.extend(
+                                records
+                                    .iter()
+                                    .filter_map(|cpu| cpu.frequency)
+                                    .map(|frequency| frequency.to_string()),
                             );
                         }
-                    }
-                }
-                None => {
-                    println!("No action specified for CPU command");
+                        if *temperature {
+                            lines.push(match cpu_temperature {
+                                Some(_) => sensors_controller::format_celsius(cpu_temperature),
+                                None => "CPU temperature is not available".to_string(),
+                            });
+                        }
+                        lines.join("\n")
+                    })?;
                     logger_control::log(
-                        "No action specified for CPU command",
-                        logger_control::LogLevel::ERROR,
+                        &format!(
+                            "CPU show called all={} usage={} frequency={} temperature={}",
+                            all, usage, frequency, temperature
+                        ),
+                        logger_control::LogLevel::INFO,
                     );
                 }
+                Some(CPUCommands::Watch {
+                    interval,
+                    count,
+                    per_core,
+                }) => {
+                    watch::cpu(
+                        format,
+                        &WatchOptions {
+                            interval: Duration::from_secs(*interval),
+                            count: *count,
+                        },
+                        *per_core,
+                    )?;
+                }
+                None => {
+                    return Err(JweError::InvalidInput(
+                        "No action specified for CPU command".to_string(),
+                    ));
+                }
             }
         }
 
@@ -296,104 +1025,251 @@ fn windows_cmd(args: Args) {
                     free,
                     used,
                     available,
+                    swap,
+                    percent,
+                    pressure,
+                    warn_at,
+                    critical_at,
+                    top,
                 }) => {
-                    if *all {
-                        let total_memory = sys.total_memory();
-                        println!("Total Memory: {} GB", bytes_to_gb(total_memory));
-                        logger_control::log(
-                            &format!("Total memory all called {}", total_memory),
-                            logger_control::LogLevel::INFO,
-                        );
-                    }
-                    if *free {
-                        let free_memory = sys.free_memory();
-                        println!("Free Memory: {} GB", bytes_to_gb(free_memory));
-                        logger_control::log(
-                            &format!("Free memory free called {}", free_memory),
-                            logger_control::LogLevel::INFO,
-                        );
-                    }
-                    if *used {
-                        let used_memory = sys.used_memory();
-                        println!("Used Memory: {} GB", bytes_to_gb(used_memory));
-                        logger_control::log(
-                            &format!("Used memory used called {}", used_memory),
-                            logger_control::LogLevel::INFO,
-                        );
-                    }
-                    if *available {
-                        let available_memory = sys.available_memory();
-                        println!("Available Memory: {} GB", bytes_to_gb(available_memory));
-                        logger_control::log(
-                            &format!("Available memory available called {}", available_memory),
-                            logger_control::LogLevel::INFO,
-                        );
+                    let pressure_percent =
+                        pressure.then(|| memory_controller::pressure_percent(&sys));
+                    let pressure_level = match pressure_percent {
+                        Some(pressure_percent) => {
+                            let settings = data_controller::read_settings()?;
+                            let thresholds = Thresholds {
+                                warn: warn_at.unwrap_or(settings.memory_warn_percent),
+                                critical: critical_at.unwrap_or(settings.memory_critical_percent),
+                            };
+                            config_controller::check_memory_thresholds(
+                                thresholds.warn,
+                                thresholds.critical,
+                            )?;
+                            Some(memory_controller::pressure_level(
+                                pressure_percent,
+                                &thresholds,
+                            ))
+                        }
+                        None => None,
+                    };
+
+                    let record = MemoryRecord {
+                        total: all.then(|| sys.total_memory()),
+                        free: free.then(|| sys.free_memory()),
+                        used: used.then(|| sys.used_memory()),
+                        available: available.then(|| sys.available_memory()),
+                        used_percent: percent
+                            .then(|| units::percent(sys.used_memory(), sys.total_memory())),
+                        swap_total: swap.then(|| sys.total_swap()),
+                        swap_used: swap.then(|| sys.used_swap()),
+                        swap_free: swap.then(|| sys.free_swap()),
+                        swap_used_percent: (*swap && *percent)
+                            .then(|| units::percent(sys.used_swap(), sys.total_swap())),
+                        pressure: pressure_level,
+                        pressure_percent,
+                    };
+                    let processes = top.map(|top| {
+                        let users = Users::new_with_refreshed_list();
+                        process_controller::select(
+                            process_controller::records(&sys, &users),
+                            &ProcessFilter::default(),
+                            ProcessSort::Mem,
+                            Some(top),
+                        )
+                    });
+
+                    let message = format!("Memory show called {:?}", record);
+                    // CSV は入れ子にできないのでプロセス一覧は出さない
+                    match processes {
+                        Some(processes) if format != OutputFormat::Csv => {
+                            let report = MemoryReport {
+                                memory: record,
+                                processes,
+                            };
+                            output::print_one(format, &report, || {
+                                memory_controller::render(&report.memory, Some(&report.processes))
+                            })?;
+                        }
+                        _ => output::print_one(format, &record, || {
+                            memory_controller::render(&record, None)
+                        })?,
                     }
+                    logger_control::log(&message, logger_control::LogLevel::INFO);
+                }
+                Some(MemShowCommands::Watch { interval, count }) => {
+                    watch::memory(
+                        format,
+                        &WatchOptions {
+                            interval: Duration::from_secs(*interval),
+                            count: *count,
+                        },
+                    )?;
                 }
                 None => {
-                    println!("No action specified for Mem command");
-                    logger_control::log(
-                        "No action specified for Mem command",
-                        logger_control::LogLevel::ERROR,
-                    );
+                    return Err(JweError::InvalidInput(
+                        "No action specified for Mem command".to_string(),
+                    ));
                 }
             }
         }
 
         // ls command
-        Some(Commands::Ls { action }) => match fs::read_dir(".") {
-            Ok(entries) => {
-                let mut dir_files: Vec<String> = Vec::new();
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        if *action {
-                            let path_string = entry.path().to_string_lossy().to_string();
-                            dir_files.push(path_string);
-                        } else {
-                            dir_files.push(entry.file_name().to_string_lossy().to_string());
-                        }
-                    }
+        Some(Commands::Ls {
+            path,
+            action,
+            long,
+            all,
+            sort,
+            reverse,
+            dirs_first,
+        }) => {
+            let path = path.clone().unwrap_or_else(|| PathBuf::from("."));
+            let records = file_controller::list(
+                &path,
+                &ListOptions {
+                    all: *all,
+                    sort: *sort,
+                    reverse: *reverse,
+                    dirs_first: *dirs_first,
+                },
+            )?;
+
+            output::print(format, &records, || {
+                if *long {
+                    file_controller::long_table(&records, *action)
+                } else {
+                    file_controller::grid(&records, *action)
                 }
-                dir_files.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
-                const ITEMS_PER_ROW: usize = 3;
-                let chunks: Vec<Vec<String>> = dir_files
-                    .chunks(ITEMS_PER_ROW)
-                    .map(|x| x.to_vec())
-                    .collect();
-                let mut builder = Builder::default();
-
-                for chunk in chunks {
-                    builder.push_record(chunk);
-                }
-                let mut table = builder.build();
-                table
-                    .with(Style::ascii_rounded())
-                    .with(Padding::new(1, 1, 0, 0))
-                    .with(Alignment::left())
-                    .with(Modify::new(Columns::new(..)).with(Width::wrap(30)))
-                    .with(Modify::new(Rows::new(..)).with(Padding::new(0, 1, 0, 0)));
-                println!("{}", table.to_string());
-                logger_control::log("Ls command called", logger_control::LogLevel::INFO);
-            }
-            Err(e) => {
-                println!("Error: {}", e);
-                logger_control::log(&format!("Error: {}", e), logger_control::LogLevel::ERROR);
-            }
-        },
+            })?;
+            logger_control::log(
+                &format!("Ls command called for {}", path.display()),
+                logger_control::LogLevel::INFO,
+            );
+        }
+        // tree command
+        Some(Commands::Tree {
+            path,
+            depth,
+            ignore,
+            no_gitignore,
+            all,
+        }) => {
+            let path = path.clone().unwrap_or_else(|| PathBuf::from("."));
+            let records = tree::tree(
+                &path,
+                &WalkOptions {
+                    depth: *depth,
+                    ignore: ignore.clone(),
+                    gitignore: !no_gitignore,
+                    all: *all,
+                },
+            )?;
+            output::print(format, &records, || tree::render_tree(&path, &records))?;
+            logger_control::log(
+                &format!("Tree called for {}", path.display()),
+                logger_control::LogLevel::INFO,
+            );
+        }
+
+        // du command
+        Some(Commands::Du {
+            path,
+            top,
+            ignore,
+            no_gitignore,
+            all,
+        }) => {
+            let path = path.clone().unwrap_or_else(|| PathBuf::from("."));
+            let records = tree::du(
+                &path,
+                &WalkOptions {
+                    depth: None,
+                    ignore: ignore.clone(),
+                    gitignore: !no_gitignore,
+                    all: *all,
+                },
+            )?;
+            let shown = &records[..(*top).min(records.len())];
+            output::print(format, shown, || tree::du_table(shown, &records))?;
+            logger_control::log(
+                &format!("Du called for {}", path.display()),
+                logger_control::LogLevel::INFO,
+            );
+        }
+
+        // find command
+        Some(Commands::Find {
+            pattern,
+            path,
+            regex,
+            kind,
+            min_size,
+            max_size,
+            newer,
+            older,
+            grep,
+            ignore_case,
+            depth,
+            ignore,
+            no_gitignore,
+            all,
+            long,
+        }) => {
+            let path = path.clone().unwrap_or_else(|| PathBuf::from("."));
+            let records = find::find(
+                &path,
+                &find::parse_pattern(pattern, *regex)?,
+                &FindOptions {
+                    walk: WalkOptions {
+                        depth: *depth,
+                        ignore: ignore.clone(),
+                        gitignore: !no_gitignore,
+                        all: *all,
+                    },
+                    kind: *kind,
+                    min_size: *min_size,
+                    max_size: *max_size,
+                    newer: *newer,
+                    older: *older,
+                    grep: grep
+                        .as_deref()
+                        .map(|grep| find::content_regex(grep, *ignore_case))
+                        .transpose()?,
+                },
+            )?;
+
+            output::print(format, &records, || {
+                if *long {
+                    file_controller::long_table(&records, true)
+                } else {
+                    file_controller::grid(&records, true)
+                }
+            })?;
+            logger_control::log(
+                &format!(
+                    "Find called for {} in {}, {} matches",
+                    pattern,
+                    path.display(),
+                    records.len()
+                ),
+                logger_control::LogLevel::INFO,
+            );
+        }
+
         None => {
-            println!("No subcommand was used");
-            logger_control::log("No subcommand was used", logger_control::LogLevel::ERROR);
+            return Err(JweError::InvalidInput("No subcommand was used".to_string()));
         }
 
         // browser command
         Some(Commands::Browser { action }) => {
-            let settings = data_controller::read_settings();
+            let settings = data_controller::read_settings()?;
             match action {
                 Some(BrowserCommands::Show {
                     all,
                     set,
                     reset,
                     set_search,
+                    engine,
                 }) => {
                     if *all {
                         println!("Browser: {}", settings.browser);
@@ -404,12 +1280,9 @@ fn windows_cmd(args: Args) {
                     }
 
                     if let Some(set) = set {
-                        let new_settings = data_controller::Settings {
-                            version: settings.version.clone(),
-                            browser: set.to_string(),
-                            web_search: settings.web_search.clone(),
-                        };
-                        data_controller::write_settings(new_settings);
+                        let mut new_settings = data_controller::read_settings()?;
+                        new_settings.browser = set.to_string();
+                        data_controller::write_settings(new_settings)?;
                         logger_control::log(
                             &format!("Browser set set called {}", set),
                             logger_control::LogLevel::INFO,
@@ -417,46 +1290,50 @@ fn windows_cmd(args: Args) {
                     }
 
                     if *reset {
-                        let new_settings = data_controller::Settings {
-                            version: settings.version.clone(),
-                            browser: "Default".to_string(),
-                            web_search: settings.web_search.clone(),
-                        };
-                        data_controller::write_settings(new_settings);
+                        let mut new_settings = data_controller::read_settings()?;
+                        new_settings.browser = "Default".to_string();
+                        data_controller::write_settings(new_settings)?;
                         logger_control::log(
-                            &format!("Browser reset reset called"),
+                            "Browser reset reset called",
                             logger_control::LogLevel::INFO,
                         );
                     }
 
-                    if *set_search {
+                    if let Some(engine) = engine {
+                        let mut new_settings = data_controller::read_settings()?;
+                        new_settings.web_search =
+                            config_controller::normalize_search_engine(engine)?;
+                        data_controller::write_settings(new_settings)?;
+                        logger_control::log(
+                            &format!("Browser set_search engine called {}", engine),
+                            logger_control::LogLevel::INFO,
+                        );
+                    } else if *set_search {
+                        if !prompt::is_interactive() {
+                            return Err(JweError::InvalidInput(
+                                "Missing search engine (use --engine when stdin is not a terminal)"
+                                    .to_string(),
+                            ));
+                        }
                         println!("Please enter the search engine you would like to use");
-                        println!("Options: 1:DuckDuckGo 2:Google");
-                        let mut input = String::new();
-
-                        print!("Enter your choice: ");
-                        io::stdout().flush().unwrap();
-
-                        io::stdin()
-                            .read_line(&mut input)
-                            .expect("Failed to read line");
-
-                        let input = input.trim();
-
-                        let new_settings = data_controller::Settings {
-                            version: settings.version.clone(),
-                            browser: settings.browser.clone(),
-                            web_search: match input {
-                                "1" => "DuckDuckGo".to_string(),
-                                "2" => "Google".to_string(),
-                                _ => {
+                        println!("Options: 1:DuckDuckGo 2:Google 3:Bing");
+                        let input = prompt::read_line("Enter your choice: ")?;
+
+                        let mut new_settings = data_controller::read_settings()?;
+                        new_settings.web_search = match input.as_str() {
+                            "1" => "DuckDuckGo".to_string(),
+                            "2" => "Google".to_string(),
+                            "3" => "Bing".to_string(),
+                            _ => match config_controller::normalize_search_engine(&input) {
+                                Ok(engine) => engine,
+                                Err(_) => {
                                     println!("Invalid input, defaulting to DuckDuckGo");
                                     "DuckDuckGo".to_string()
                                 }
                             },
                         };
 
-                        data_controller::write_settings(new_settings);
+                        data_controller::write_settings(new_settings)?;
                         logger_control::log(
                             &format!("Browser set_search set_search called {}", input),
                             logger_control::LogLevel::INFO,
@@ -464,143 +1341,45 @@ fn windows_cmd(args: Args) {
                     }
                 }
                 None => {
-                    println!("No action specified for Browser command");
-                    logger_control::log(
-                        "No action specified for Browser command",
-                        logger_control::LogLevel::ERROR,
-                    );
+                    return Err(JweError::InvalidInput(
+                        "No action specified for Browser command".to_string(),
+                    ));
                 }
 
                 Some(BrowserCommands::Fav {
+                    action,
                     add_favorite,
                     remove_favorite,
                     list_favorite,
                     open_favorite,
                 }) => {
+                    match action {
+                        Some(FavCommands::Add { name, url }) => {
+                            fav_add(name.as_deref(), url.as_deref())?
+                        }
+                        Some(FavCommands::Remove { name }) => fav_remove(name.as_deref())?,
+                        Some(FavCommands::List) => fav_list(format)?,
+                        Some(FavCommands::Open { name }) => fav_open(name, &settings.web_search)?,
+                        None => {}
+                    }
+
                     if *add_favorite {
-                        data_controller::init_favorites();
-
-                        let mut favorites = data_controller::read_favorites();
-                        println!("Please enter the name of the favorite URL");
-                        let mut name = String::new();
-                        print!("Enter the name: ");
-                        io::stdout().flush().unwrap();
-                        io::stdin()
-                            .read_line(&mut name)
-                            .expect("Failed to read line");
-                        let name = name.trim();
-
-                        println!("Please enter the URL");
-                        let mut url = String::new();
-                        print!("Enter the URL: ");
-                        io::stdout().flush().unwrap();
-                        io::stdin()
-                            .read_line(&mut url)
-                            .expect("Failed to read line");
-                        let url = url.trim();
-
-                        favorites
-                            .favorites
-                            .insert(name.to_string(), url.to_string());
-                        data_controller::write_favorites(favorites);
-                        logger_control::log(
-                            &format!("Browser favorite favorite called {}", name),
-                            logger_control::LogLevel::INFO,
-                        );
+                        fav_add(None, None)?;
                     }
                     if *remove_favorite {
-                        let favorites = data_controller::read_favorites();
-                        let mut favorites_map = favorites.favorites;
-                        let mut keys: Vec<String> = Vec::new();
-                        for key in favorites_map.keys() {
-                            keys.push(key.to_string());
-                        }
-                        let mut builder = Builder::default();
-                        builder.push_record(keys);
-                        let mut table = builder.build();
-                        table.with(Style::ascii_rounded());
-                        println!("{}", table.to_string());
-
-                        println!(
-                            "Please enter the name of the favorite URL you would like to remove"
-                        );
-                        let mut name = String::new();
-                        print!("Enter the name: ");
-                        io::stdout().flush().unwrap();
-                        io::stdin()
-                            .read_line(&mut name)
-                            .expect("Failed to read line");
-                        let name = name.trim();
-
-                        favorites_map.remove(name);
-                        let new_favorites = data_controller::Favorites {
-                            favorites: favorites_map,
-                        };
-                        data_controller::write_favorites(new_favorites);
-                        logger_control::log(
-                            &format!("Browser favorite remove_favorite called {}", name),
-                            logger_control::LogLevel::INFO,
-                        );
+                        fav_remove(None)?;
                     }
-
                     if *list_favorite {
-                        let favorites = data_controller::read_favorites();
-                        let favorites_map = favorites.favorites;
-                        let mut keys: Vec<String> = Vec::new();
-                        for key in favorites_map.keys() {
-                            keys.push(key.to_string());
-                        }
-                        keys.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
-                        const ITEMS_PER_ROW: usize = 3;
-                        let chunks: Vec<Vec<String>> =
-                            keys.chunks(ITEMS_PER_ROW).map(|x| x.to_vec()).collect();
-                        let mut builder = Builder::default();
-
-                        for chunk in chunks {
-                            builder.push_record(chunk);
-                        }
-                        let mut table = builder.build();
-                        table
-                            .with(Style::ascii_rounded())
-                            .with(Padding::new(1, 1, 0, 0))
-                            .with(Alignment::left())
-                            .with(Modify::new(Columns::new(..)).with(Width::wrap(30)))
-                            .with(Modify::new(Rows::new(..)).with(Padding::new(0, 1, 0, 0)));
-                        println!("{}", table.to_string());
-                        logger_control::log(
-                            "Browser favorite list_favorite called",
-                            logger_control::LogLevel::INFO,
-                        );
+                        fav_list(format)?;
                     }
-
-                    if let Some(oepn_favorite) = open_favorite {
-                        let favorites = data_controller::read_favorites();
-                        let favorites_map = favorites.favorites;
-                        if let Some(favorite_url) = favorites_map.get(oepn_favorite) {
-                            libs::browser_controller::search_in_browser(
-                                &favorite_url,
-                                &settings.web_search,
-                            );
-                            logger_control::log(
-                                &format!(
-                                    "Browser favorite open_favorite open_favorite called {}",
-                                    oepn_favorite
-                                ),
-                                logger_control::LogLevel::INFO,
-                            );
-                        } else {
-                            println!("Favorite URL not found");
-                            logger_control::log(
-                                "Favorite URL not found",
-                                logger_control::LogLevel::ERROR,
-                            );
-                        }
+                    if let Some(open_favorite) = open_favorite {
+                        fav_open(open_favorite, &settings.web_search)?;
                     }
                 }
 
                 Some(BrowserCommands::Search { query }) => {
-                    let settings = data_controller::read_settings();
-                    libs::browser_controller::search_in_browser(query, &settings.web_search);
+                    let settings = data_controller::read_settings()?;
+                    libs::browser_controller::search_in_browser(query, &settings.web_search)?;
                     logger_control::log(
                         &format!("Browser search search called {}", query),
                         logger_control::LogLevel::INFO,
@@ -611,130 +1390,53 @@ fn windows_cmd(args: Args) {
 
         // open command
         Some(Commands::Open { action }) => {
-            let appdata_content = win_api::get_appdata();
+            let appdata_content = platform.get_appdata();
             match action {
                 Some(OpenCommands::Appdata { user }) => {
-                    if let Some(appdata_content) = appdata_content {
-                        if user.clone().unwrap().len() > 0 {
-                            if let Err(e) = win_api::open_explorer(appdata_content) {
-                                println!("Failed to open Appdata: {}", e);
-                                logger_control::log(
-                                    &format!("Failed to open Appdata: {}", e),
-                                    logger_control::LogLevel::ERROR,
-                                );
-                            }
-                        } else {
-                            if let Err(e) = win_api::open_explorer(appdata_content.join("Local")) {
-                                println!("Failed to open Local Appdata: {}", e);
-                                logger_control::log(
-                                    &format!("Failed to open Local Appdata: {}", e),
-                                    logger_control::LogLevel::ERROR,
-                                );
-                            }
-                        }
+                    let appdata_content = appdata_content.ok_or_else(|| {
+                        JweError::NotFound("Failed to get Appdata directory".to_string())
+                    })?;
+                    if user.as_deref().is_some_and(|user| !user.is_empty()) {
+                        platform.open_explorer(&appdata_content)?;
                     } else {
-                        println!("Failed to get Appdata directory");
-                        logger_control::log(
-                            "Failed to get Appdata directory",
-                            logger_control::LogLevel::ERROR,
-                        );
+                        platform.open_explorer(&appdata_content.join("Local"))?;
                     }
                 }
 
                 Some(OpenCommands::Local) => {
-                    let local_appdata = win_api::get_local_appdata();
-                    if let Some(local_appdata) = local_appdata {
-                        if let Err(e) = win_api::open_explorer(local_appdata) {
-                            println!("Failed to open Local Appdata: {}", e);
-                            logger_control::log(
-                                &format!("Failed to open Local Appdata: {}", e),
-                                logger_control::LogLevel::ERROR,
-                            );
-                        }
-                    } else {
-                        println!("Failed to get Appdata directory");
-                        logger_control::log(
-                            "Failed to get Appdata directory",
-                            logger_control::LogLevel::ERROR,
-                        );
-                    }
+                    let local_appdata = platform.get_local_appdata().ok_or_else(|| {
+                        JweError::NotFound("Failed to get Appdata directory".to_string())
+                    })?;
+                    platform.open_explorer(&local_appdata)?;
                 }
 
                 Some(OpenCommands::LocalLow) => {
-                    let local_low = win_api::get_local_low();
-                    if let Some(local_low) = local_low {
-                        if let Err(e) = win_api::open_explorer(local_low) {
-                            println!("Failed to open LocalLow: {}", e);
-                            logger_control::log(
-                                &format!("Failed to open LocalLow: {}", e),
-                                logger_control::LogLevel::ERROR,
-                            );
-                        }
-                    } else {
-                        println!("Failed to get LocalLow directory");
-                        logger_control::log(
-                            "Failed to get LocalLow directory",
-                            logger_control::LogLevel::ERROR,
-                        );
-                    }
+                    let local_low = platform.get_local_low().ok_or_else(|| {
+                        JweError::NotFound("Failed to get LocalLow directory".to_string())
+                    })?;
+                    platform.open_explorer(&local_low)?;
                 }
 
                 Some(OpenCommands::Roaming) => {
-                    let roaming = win_api::get_roaming();
-                    if let Some(roaming) = roaming {
-                        if let Err(e) = win_api::open_explorer(roaming) {
-                            println!("Failed to open Roaming: {}", e);
-                            logger_control::log(
-                                &format!("Failed to open Roaming: {}", e),
-                                logger_control::LogLevel::ERROR,
-                            );
-                        }
-                    } else {
-                        println!("Failed to get Roaming directory");
-                        logger_control::log(
-                            "Failed to get Roaming directory",
-                            logger_control::LogLevel::ERROR,
-                        );
-                    }
+                    let roaming = platform.get_roaming().ok_or_else(|| {
+                        JweError::NotFound("Failed to get Roaming directory".to_string())
+                    })?;
+                    platform.open_explorer(&roaming)?;
                 }
 
                 Some(OpenCommands::Johma) => {
-                    let app_folder = win_api::get_app_folder();
-                    if let Some(app_folder) = app_folder {
-                        if let Err(e) = win_api::open_explorer(app_folder) {
-                            println!("Failed to open App Folder: {}", e);
-                            logger_control::log(
-                                &format!("Failed to open App Folder: {}", e),
-                                logger_control::LogLevel::ERROR,
-                            );
-                        }
-                    } else {
-                        println!("Failed to get App Folder directory");
-                        logger_control::log(
-                            "Failed to get App Folder directory",
-                            logger_control::LogLevel::ERROR,
-                        );
-                    }
+                    let app_folder = platform.get_app_folder().ok_or_else(|| {
+                        JweError::NotFound("Failed to get App Folder directory".to_string())
+                    })?;
+                    platform.open_explorer(&app_folder)?;
                 }
 
                 Some(OpenCommands::TaskM) => {
-                    if let Err(e) = win_api::open_task_manager() {
-                        println!("Failed to open Task Manager: {}", e);
-                        logger_control::log(
-                            &format!("Failed to open Task Manager: {}", e),
-                            logger_control::LogLevel::ERROR,
-                        );
-                    }
+                    platform.open_task_manager()?;
                 }
 
                 Some(OpenCommands::Env) => {
-                    if let Err(e) = win_api::open_environment_variables_window() {
-                        println!("Failed to open Environment Variables: {}", e);
-                        logger_control::log(
-                            &format!("Failed to open Environment Variables: {}", e),
-                            logger_control::LogLevel::ERROR,
-                        );
-                    }
+                    platform.open_environment_variables_window()?;
                     logger_control::log(
                         "Opened Environment Variables",
                         logger_control::LogLevel::INFO,
@@ -742,53 +1444,161 @@ fn windows_cmd(args: Args) {
                 }
 
                 Some(OpenCommands::There) => {
-                    if let Err(e) = win_api::open_explorer(".") {
-                        println!("Failed to open current directory: {}", e);
-                        logger_control::log(
-                            &format!("Failed to open current directory: {}", e),
-                            logger_control::LogLevel::ERROR,
-                        );
-                    }
+                    platform.open_explorer(Path::new("."))?;
                 }
 
-                Some(OpenCommands::AllSid) => match win_api::get_all_user_sids() {
-                    Ok(sids) => {
-                        for sid in sids {
-                            println!("{}", sid);
-                        }
-                    }
-                    Err(e) => {
-                        println!("Failed to get all SIDs: {}", e);
-                        logger_control::log(
-                            &format!("Failed to get all SIDs: {}", e),
-                            logger_control::LogLevel::ERROR,
-                        );
-                    }
-                },
+                Some(OpenCommands::AllSid) => {
+                    let records: Vec<SidRecord> = platform
+                        .get_all_user_sids()?
+                        .into_iter()
+                        .map(|sid| SidRecord { sid })
+                        .collect();
+                    output::print(format, &records, || {
+                        records
+                            .iter()
+                            .map(|record| record.sid.as_str())
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    })?;
+                }
 
                 None => {
-                    println!("No action specified for Open command");
-                    logger_control::log(
-                        "No action specified for Open command",
-                        logger_control::LogLevel::ERROR,
-                    );
+                    return Err(JweError::InvalidInput(
+                        "No action specified for Open command".to_string(),
+                    ));
                 }
             }
         }
 
         // remove command
-        Some(Commands::Rm { remove }) => {
-            if let Some(remove) = remove {
-                if let Err(e) = fs::remove_file(remove) {
-                    println!("Failed to remove file: {}", e);
-                    logger_control::log(
-                        &format!("Failed to remove file: {}", e),
-                        logger_control::LogLevel::ERROR,
-                    );
+        Some(Commands::Rm {
+            paths,
+            recursive,
+            permanent,
+            dry_run,
+            yes,
+        }) => {
+            let targets = trash::targets(paths, *recursive)?;
+
+            // 少しだけ消す時は確認しない
+            let files: u64 = targets.iter().map(|target| target.files).sum();
+            if !*yes && !*dry_run && files >= trash::CONFIRM_THRESHOLD {
+                if prompt::is_interactive() {
+                    println!("{}", trash::targets_table(&targets));
+                }
+                let action = if *permanent {
+                    "Permanently delete"
+                } else {
+                    "Move to trash"
+                };
+                if !prompt::confirm(&format!("{} {} files?", action, files))? {
+                    println!("Cancelled");
+                    return Ok(());
                 }
             }
+
+            let records = trash::remove(
+                &targets,
+                &RemoveOptions {
+                    permanent: *permanent,
+                    dry_run: *dry_run,
+                },
+            );
+            output::print(format, &records, || trash::table(&records))?;
+            logger_control::log(
+                &format!("Rm called for {} items", records.len()),
+                logger_control::LogLevel::INFO,
+            );
+
+            let failed = records.iter().filter(|record| !record.success).count();
+            if failed > 0 {
+                return Err(JweError::Io(std::io::Error::other(format!(
+                    "{} of {} items could not be removed",
+                    failed,
+                    records.len()
+                ))));
+            }
         }
 
+        // trash command
+        Some(Commands::Trash { action }) => match action {
+            None => {
+                return Err(JweError::InvalidInput(
+                    "No action specified for Trash command".to_string(),
+                ));
+            }
+            Some(TrashCommands::List) => {
+                let records = trash::list()?;
+                output::print(format, &records, || {
+                    if records.is_empty() {
+                        "Trash is empty".to_string()
+                    } else {
+                        trash::list_table(&records)
+                    }
+                })?;
+                logger_control::log("Trash list called", logger_control::LogLevel::INFO);
+            }
+            Some(TrashCommands::Restore {
+                target,
+                rename,
+                overwrite,
+            }) => {
+                let conflict = if *rename {
+                    Conflict::Rename
+                } else if *overwrite {
+                    Conflict::Overwrite
+                } else {
+                    Conflict::Fail
+                };
+                let (record, destination) = trash::restore(target, conflict)?;
+                println!("Restored {} to {}", record.id, destination.display());
+                logger_control::log(
+                    &format!("Trash restore called {}", record.id),
+                    logger_control::LogLevel::INFO,
+                );
+            }
+            Some(TrashCommands::Purge {
+                older_than,
+                all: _,
+                dry_run,
+                yes,
+            }) => {
+                let targets = trash::purge_targets(*older_than)?;
+                if targets.is_empty() {
+                    println!("Nothing to purge");
+                    return Ok(());
+                }
+                if !*yes && !*dry_run {
+                    if prompt::is_interactive() {
+                        println!("{}", trash::list_table(&targets));
+                    }
+                    if !prompt::confirm(&format!(
+                        "Permanently delete {} trash items?",
+                        targets.len()
+                    ))? {
+                        println!("Cancelled");
+                        return Ok(());
+                    }
+                }
+
+                let records = trash::purge(&targets, *dry_run);
+                output::print(format, &records, || trash::table(&records))?;
+                logger_control::log(
+                    &format!("Trash purge called for {} items", records.len()),
+                    logger_control::LogLevel::INFO,
+                );
+
+                let failed = records.iter().filter(|record| !record.success).count();
+                if failed > 0 {
+                    return Err(JweError::Io(std::io::Error::other(format!(
+                        "{} of {} trash items could not be deleted",
+                        failed,
+                        records.len()
+                    ))));
+                }
+            }
+        },
+
         // update command
         Some(Commands::Update { update }) => {
             if *update {
@@ -797,6 +1607,39 @@ fn windows_cmd(args: Args) {
             }
         }
 
+        // config command
+        Some(Commands::Config { action }) => match action {
+            None => {
+                return Err(JweError::InvalidInput(
+                    "No action specified for Config command".to_string(),
+                ));
+            }
+            Some(ConfigCommands::Get { key }) => {
+                let settings = data_controller::read_settings()?;
+                let record = config_controller::record(&settings, key)?;
+                // table の時はスクリプトから使いやすいように値だけを出す
+                output::print_one(format, &record, || record.value.clone())?;
+            }
+            Some(ConfigCommands::Set { key, value }) => {
+                let mut settings = data_controller::read_settings()?;
+                config_controller::set(&mut settings, key, value)?;
+                data_controller::write_settings(settings)?;
+            }
+            Some(ConfigCommands::Unset { key }) => {
+                let mut settings = data_controller::read_settings()?;
+                config_controller::unset(&mut settings, key)?;
+                data_controller::write_settings(settings)?;
+            }
+            Some(ConfigCommands::List) => {
+                let records = config_controller::list(&data_controller::read_settings()?);
+                output::print(format, &records, || config_controller::table(&records))?;
+                logger_control::log("Config list called", logger_control::LogLevel::INFO);
+            }
+            Some(action @ (ConfigCommands::Path | ConfigCommands::Edit)) => {
+                config_file_cmd(action)?;
+            }
+        },
+
         // version command
         Some(Commands::Version) => {
             println!("Version: {}", VERISON);
@@ -809,7 +1652,7 @@ fn windows_cmd(args: Args) {
         // explorer command
         Some(Commands::Expl { reflesh }) => {
             if *reflesh {
-                let _ = win_api::refresh_exprorer();
+                platform.refresh_explorer()?;
                 logger_control::log("Explorer reflesh called", logger_control::LogLevel::INFO);
             }
         }
@@ -817,96 +1660,345 @@ fn windows_cmd(args: Args) {
         // proc command
         Some(Commands::Proc { action }) => match action {
             None => {
-                println!("No action specified for Proc command");
+                return Err(JweError::InvalidInput(
+                    "No action specified for Proc command".to_string(),
+                ));
+            }
+            Some(ProcCommands::Show {
+                // --all は以前の書き方との互換のために残している
+                all: _,
+                name,
+                user,
+                sort,
+                top,
+                tree,
+            }) => {
+                let filter = ProcessFilter {
+                    name: name.clone(),
+                    user: user.clone(),
+                };
+                let records =
+                    process_controller::select(platform.list_processes()?, &filter, *sort, *top);
+                output::print(format, &records, || {
+                    if *tree {
+                        process_controller::tree(&records)
+                    } else {
+                        process_controller::table(&records)
+                    }
+                })?;
+                logger_control::log("Proc show called", logger_control::LogLevel::INFO);
+            }
+            Some(ProcCommands::Top {
+                interval,
+                sort,
+                name,
+            }) => {
+                top::run(
+                    platform,
+                    TopOptions {
+                        interval: Duration::from_secs(*interval),
+                        sort: *sort,
+                        name: name.clone(),
+                    },
+                )?;
+            }
+            Some(ProcCommands::Kill {
+                target,
+                pid,
+                regex,
+                tree,
+                force,
+                timeout,
+                yes,
+            }) => {
+                let process_target = match (pid, target) {
+                    (Some(pid), _) => ProcessTarget::Pid(*pid),
+                    (None, Some(target)) => process_controller::parse_target(target, *regex)?,
+                    (None, None) => {
+                        return Err(JweError::InvalidInput(
+                            "No process specified for Proc kill command".to_string(),
+                        ));
+                    }
+                };
+
+                let targets = process_controller::find_targets(
+                    &platform.list_processes()?,
+                    &process_target,
+                    *tree,
+                );
+                if targets.is_empty() {
+                    return Err(JweError::NotFound(format!(
+                        "No process matches {}",
+                        target
+                            .clone()
+                            .or_else(|| pid.map(|pid| pid.to_string()))
+                            .unwrap_or_default()
+                    )));
+                }
+
+                // PID を 1 つだけ指定した時は確認しない
+                let single_pid =
+                    matches!(process_target, ProcessTarget::Pid(_)) && targets.len() == 1;
+                if !*yes && !single_pid {
+                    if prompt::is_interactive() {
+                        println!("{}", process_controller::table(&targets));
+                    }
+                    if !prompt::confirm(&format!("Kill {} processes?", targets.len()))? {
+                        println!("Cancelled");
+                        return Ok(());
+                    }
+                }
+
+                let records = process_controller::kill(
+                    platform,
+                    &targets,
+                    &KillOptions {
+                        force: *force,
+                        timeout: Duration::from_secs(*timeout),
+                    },
+                );
+                output::print(format, &records, || {
+                    let mut builder = Builder::default();
+                    builder.push_record(["PID", "Name", "Result", "Message"]);
+                    for record in &records {
+                        builder.push_record([
+                            record.pid.to_string(),
+                            record.name.clone(),
+                            if record.success { "ok" } else { "failed" }.to_string(),
+                            record.message.clone(),
+                        ]);
+                    }
+                    let mut table = builder.build();
+                    table.with(Style::ascii_rounded());
+                    table.to_string()
+                })?;
                 logger_control::log(
-                    "No action specified for Proc command",
-                    logger_control::LogLevel::ERROR,
+                    &format!("Proc kill called for {} processes", records.len()),
+                    logger_control::LogLevel::INFO,
                 );
-            }
-            Some(ProcCommands::Show { all }) => {
-                if *all {
-                    win_api::show_all_pid();
-                    logger_control::log(
-                        "CPU all_pid all_pid called",
-                        logger_control::LogLevel::INFO,
-                    );
+
+                let failed = records.iter().filter(|record| !record.success).count();
+                if failed > 0 {
+                    return Err(JweError::Platform(format!(
+                        "{} of {} processes are still running",
+                        failed,
+                        records.len()
+                    )));
                 }
             }
-            Some(ProcCommands::Kill { pid }) => {
-                if let Err(e) = win_api::kill_pid(*pid) {
-                    println!("Failed to kill process: {}", e);
-                    logger_control::log(
-                        &format!("Failed to kill process: {}", e),
-                        logger_control::LogLevel::ERROR,
-                    );
+        },
+
+        // disk command
+        Some(Commands::Disk { action }) => match action {
+            None => {
+                return Err(JweError::InvalidInput(
+                    "No action specified for Disk command".to_string(),
+                ));
+            }
+            Some(DiskCommands::Show { warn_below }) => {
+                let records = disk_controller::list(*warn_below);
+                output::print(format, &records, || disk_controller::table(&records))?;
+                logger_control::log("Disk show called", logger_control::LogLevel::INFO);
+
+                if let Some(warn_below) = warn_below {
+                    disk_controller::check(&records, *warn_below)?;
                 }
             }
         },
 
-        // lc command
-        Some(Commands::Lc { action }) => match action {
+        // sysinfo command
+        Some(Commands::Sysinfo) => {
+            let report = system_controller::report();
+            // CSV は入れ子にできないので概要だけを出す
+            if format == OutputFormat::Csv {
+                output::print(format, std::slice::from_ref(&report.overview), String::new)?;
+            } else {
+                output::print_one(format, &report, || system_controller::render(&report))?;
+            }
+            logger_control::log("Sysinfo called", logger_control::LogLevel::INFO);
+        }
+
+        // sensors command
+        Some(Commands::Sensors {
+            warn_above,
+            warn_critical,
+        }) => {
+            let records = sensors_controller::list(&SensorAlert {
+                warn_above: *warn_above,
+                critical: *warn_critical,
+            });
+            output::print(format, &records, || sensors_controller::table(&records))?;
+            logger_control::log(
+                &format!("Sensors show called for {} sensors", records.len()),
+                logger_control::LogLevel::INFO,
+            );
+            sensors_controller::check(&records)?;
+        }
+
+        // net command
+        Some(Commands::Net { action }) => match action {
             None => {
-                println!("No action specified for Lc command");
-                logger_control::log(
-                    "No action specified for Lc command",
-                    logger_control::LogLevel::ERROR,
-                );
+                return Err(JweError::InvalidInput(
+                    "No action specified for Net command".to_string(),
+                ));
+            }
+            Some(NetCommands::Show) => {
+                let records = network_controller::list();
+                output::print(format, &records, || network_controller::table(&records))?;
+                logger_control::log("Net show called", logger_control::LogLevel::INFO);
+            }
+            Some(NetCommands::Watch {
+                interval,
+                count,
+                interface,
+            }) => {
+                network_controller::watch(
+                    format,
+                    &WatchOptions {
+                        interval: Duration::from_secs(*interval),
+                        count: *count,
+                    },
+                    interface.as_deref(),
+                )?;
             }
-            Some(LcCommands::Show { all }) => {
-                if *all {
-                    data_controller::init_launcher().expect("Failed to init launcher");
+        },
 
-                    let launchers = data_controller::read_launcher().launchers;
-                    let mut keys: Vec<String> = Vec::new();
-                    for key in launchers.keys() {
-                        keys.push(key.to_string());
-                    }
-                    keys.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
-                    const ITEMS_PER_ROW: usize = 3;
-                    let chunks: Vec<Vec<String>> =
-                        keys.chunks(ITEMS_PER_ROW).map(|x| x.to_vec()).collect();
-                    let mut builder = Builder::default();
+        // monitor command
+        Some(Commands::Monitor { action }) => match action {
+            None => {
+                return Err(JweError::InvalidInput(
+                    "No action specified for Monitor command".to_string(),
+                ));
+            }
+            Some(MonitorCommands::Record {
+                interval,
+                count,
+                keep,
+            }) => {
+                monitor::record(
+                    &WatchOptions {
+                        interval: Duration::from_secs(*interval),
+                        count: *count,
+                    },
+                    *keep,
+                )?;
+            }
+            Some(MonitorCommands::Report { since, top }) => {
+                monitor::report(format, *since, *top)?;
+            }
+        },
 
-                    for chunk in chunks {
-                        builder.push_record(chunk);
-                    }
-                    let mut table = builder.build();
-                    table
-                        .with(Style::ascii_rounded())
-                        .with(Padding::new(1, 1, 0, 0))
-                        .with(Alignment::left())
-                        .with(Modify::new(Columns::new(..)).with(Width::wrap(30)))
-                        .with(Modify::new(Rows::new(..)).with(Padding::new(0, 1, 0, 0)));
-                    println!("{}", table.to_string());
+        // lc command
+        Some(Commands::Lc { action }) => match action {
+            None => {
+                return Err(JweError::InvalidInput(
+                    "No action specified for Lc command".to_string(),
+                ));
+            }
+            Some(LcCommands::Show { all, long }) => {
+                if *all || *long {
+                    data_controller::init_launcher()?;
+
+                    let launchers = data_controller::read_launcher()?.launchers;
+                    let mut records: Vec<LauncherRecord> = launchers
+                        .iter()
+                        .map(|(name, entry)| LauncherRecord::new(name.clone(), entry))
+                        .collect();
+                    records.sort_by_key(|record| record.name.to_lowercase());
+
+                    output::print(format, &records, || {
+                        if *long {
+                            let mut builder = Builder::default();
+                            builder.push_record([
+                                "Name",
+                                "Program",
+                                "Args",
+                                "Cwd",
+                                "Mode",
+                                "Elevated",
+                                "Tags",
+                                "Description",
+                            ]);
+                            for record in &records {
+                                builder.push_record([
+                                    record.name.clone(),
+                                    record.program.clone(),
+                                    record.args.clone(),
+                                    record.cwd.clone(),
+                                    format!("{:?}", record.mode).to_lowercase(),
+                                    record.elevated.to_string(),
+                                    record.tags.clone(),
+                                    record.description.clone(),
+                                ]);
+                            }
+                            let mut table = builder.build();
+                            table.with(Style::ascii_rounded());
+                            table.to_string()
+                        } else {
+                            let keys: Vec<String> =
+                                records.iter().map(|record| record.name.clone()).collect();
+                            output::grid(&keys)
+                        }
+                    })?;
                     logger_control::log(
                         "Launcher all_pid all_pid called",
                         logger_control::LogLevel::INFO,
                     );
                 }
             }
-            Some(LcCommands::Add) => {
-                data_controller::init_launcher().expect("Failed to init launcher");
-                let mut launchers = data_controller::read_launcher().launchers;
-
-                println!("Please enter the name of the launcher");
-                let mut name = String::new();
-                print!("Enter the name: ");
-                io::stdout().flush().unwrap();
-                io::stdin()
-                    .read_line(&mut name)
-                    .expect("Failed to read line");
-                let name = name.trim();
-
-                println!("Please enter the path of the launcher");
-                let mut path = String::new();
-                print!("Enter the path: ");
-                io::stdout().flush().unwrap();
-                io::stdin()
-                    .read_line(&mut path)
-                    .expect("Failed to read line");
-                let path = path.trim();
-
-                match launchers.insert(name.to_string(), path.to_string()) {
+            Some(LcCommands::Add {
+                name,
+                path,
+                args,
+                cwd,
+                env,
+                description,
+                tags,
+                wait,
+                elevated,
+            }) => {
+                data_controller::init_launcher()?;
+                let mut launchers = data_controller::read_launcher()?.launchers;
+
+                let name = prompt::value_or_prompt(
+                    name.as_deref(),
+                    "Please enter the name of the launcher",
+                    "name",
+                )?;
+                let path = prompt::value_or_prompt(
+                    path.as_deref(),
+                    "Please enter the path of the launcher",
+                    "path",
+                )?;
+
+                // lc run は同じ名前ならランチャーを先に使うので、グループと同じ名前は付けさせない
+                data_controller::init_launcher_groups()?;
+                if data_controller::read_launcher_groups()?
+                    .groups
+                    .contains_key(&name)
+                {
+                    return Err(JweError::InvalidInput(format!(
+                        "A launcher group named {} already exists",
+                        name
+                    )));
+                }
+
+                let entry = LauncherEntry {
+                    args: args.clone(),
+                    cwd: cwd.clone(),
+                    env: env.iter().cloned().collect(),
+                    description: description.clone(),
+                    tags: tags.clone(),
+                    mode: if *wait {
+                        LaunchMode::Wait
+                    } else {
+                        LaunchMode::Detach
+                    },
+                    elevated: *elevated,
+                    ..LauncherEntry::new(path)
+                };
+
+                match launchers.insert(name.clone(), entry) {
                     Some(_) => {
                         println!("Launcher {} already exists", name);
 
@@ -917,37 +2009,26 @@ fn windows_cmd(args: Args) {
                         println!("Launcher {} added", name);
                     }
                 }
-                data_controller::write_launcher(launchers);
+                data_controller::write_launcher(launchers)?;
                 logger_control::log(
                     &format!("Launcher add add called {}", name),
                     logger_control::LogLevel::INFO,
                 );
             }
-            Some(LcCommands::Remove) => {
-                data_controller::init_launcher().expect("Failed to init launcher");
-
-                let mut launchers = data_controller::read_launcher().launchers;
-                println!("Please enter the name of the launcher you would like to remove");
-                let mut name = String::new();
-                print!("Enter the name: ");
-                io::stdout().flush().unwrap();
-                io::stdin()
-                    .read_line(&mut name)
-                    .expect("Failed to read line");
-                let name = name.trim();
-                match launchers.remove(name) {
-                    Some(_) => {
-                        println!("Launcher removed");
-                    }
-                    None => {
-                        println!("Launcher not found");
-                        logger_control::log(
-                            &format!("Launcher not found {}", name),
-                            logger_control::LogLevel::ERROR,
-                        );
-                    }
+            Some(LcCommands::Remove { name }) => {
+                data_controller::init_launcher()?;
+
+                let mut launchers = data_controller::read_launcher()?.launchers;
+                let name = prompt::value_or_prompt(
+                    name.as_deref(),
+                    "Please enter the name of the launcher you would like to remove",
+                    "name",
+                )?;
+                if launchers.remove(&name).is_none() {
+                    return Err(JweError::NotFound(format!("Launcher not found {}", name)));
                 }
-                data_controller::write_launcher(launchers);
+                println!("Launcher removed");
+                data_controller::write_launcher(launchers)?;
                 logger_control::log(
                     &format!("Launcher remove remove called {}", name),
                     logger_control::LogLevel::INFO,
@@ -955,30 +2036,353 @@ fn windows_cmd(args: Args) {
             }
 
             Some(LcCommands::Run { name }) => {
-                data_controller::init_launcher().expect("Failed to init launcher");
+                data_controller::init_launcher()?;
 
-                let launchers = data_controller::read_launcher().launchers;
-                if let Some(launcher_path) = launchers.get(name) {
-                    if let Err(e) = win_api::run_launcher(launcher_path) {
-                        println!("Failed to run launcher: {}", e);
-                        logger_control::log(
-                            &format!("Failed to run launcher: {}", e),
-                            logger_control::LogLevel::ERROR,
-                        );
+                let launchers = data_controller::read_launcher()?.launchers;
+                match launchers.get(name) {
+                    Some(entry) => platform.run_launcher(name, entry)?,
+                    None => run_launcher_group(platform, format, name)?,
+                }
+            }
+
+            Some(LcCommands::Group { action }) => match action {
+                None => {
+                    return Err(JweError::InvalidInput(
+                        "No action specified for Lc group command".to_string(),
+                    ));
+                }
+                Some(LcGroupCommands::Add {
+                    name,
+                    launchers: members,
+                    delay,
+                    wait_started,
+                    timeout,
+                    description,
+                }) => {
+                    data_controller::init_launcher()?;
+                    data_controller::init_launcher_groups()?;
+
+                    let launchers = data_controller::read_launcher()?.launchers;
+                    if launchers.contains_key(name) {
+                        return Err(JweError::InvalidInput(format!(
+                            "A launcher named {} already exists, so lc run {} would never run the group",
+                            name, name
+                        )));
                     }
-                } else {
-                    println!("Launcher not found");
+                    if let Some(missing) = members
+                        .iter()
+                        .find(|member| !launchers.contains_key(&member.launcher))
+                    {
+                        return Err(JweError::NotFound(format!(
+                            "Launcher not found {}",
+                            missing.launcher
+                        )));
+                    }
+
+                    let group = LauncherGroup {
+                        description: description.clone(),
+                        items: members
+                            .iter()
+                            .map(|member| GroupItem {
+                                launcher: member.launcher.clone(),
+                                delay_ms: member.delay_ms.unwrap_or(*delay),
+                                wait_started: *wait_started,
+                                timeout_secs: member.timeout_secs.unwrap_or(*timeout),
+                            })
+                            .collect(),
+                    };
+
+                    let mut groups = data_controller::read_launcher_groups()?.groups;
+                    if groups.insert(name.clone(), group).is_some() {
+                        println!("Launcher group {} updated", name);
+                    } else {
+                        println!("Launcher group {} added", name);
+                    }
+                    data_controller::write_launcher_groups(groups)?;
                     logger_control::log(
-                        &format!("Launcher not found {}", name),
-                        logger_control::LogLevel::ERROR,
+                        &format!("Launcher group add called {}", name),
+                        logger_control::LogLevel::INFO,
                     );
                 }
-            }
+                Some(LcGroupCommands::Remove { name }) => {
+                    data_controller::init_launcher_groups()?;
+
+                    let mut groups = data_controller::read_launcher_groups()?.groups;
+                    if groups.remove(name).is_none() {
+                        return Err(JweError::NotFound(format!(
+                            "Launcher group not found {}",
+                            name
+                        )));
+                    }
+                    data_controller::write_launcher_groups(groups)?;
+                    println!("Launcher group removed");
+                    logger_control::log(
+                        &format!("Launcher group remove called {}", name),
+                        logger_control::LogLevel::INFO,
+                    );
+                }
+                Some(LcGroupCommands::Show { name }) => {
+                    data_controller::init_launcher_groups()?;
+
+                    let groups = data_controller::read_launcher_groups()?.groups;
+                    let mut names: Vec<&String> = match name {
+                        Some(name) if !groups.contains_key(name) => {
+                            return Err(JweError::NotFound(format!(
+                                "Launcher group not found {}",
+                                name
+                            )));
+                        }
+                        Some(name) => vec![name],
+                        None => groups.keys().collect(),
+                    };
+                    names.sort_by_key(|name| name.to_lowercase());
+
+                    let records: Vec<GroupItemRecord> = names
+                        .iter()
+                        .flat_map(|group_name| {
+                            groups[*group_name]
+                                .items
+                                .iter()
+                                .enumerate()
+                                .map(|(index, item)| GroupItemRecord {
+                                    group: group_name.to_string(),
+                                    step: index + 1,
+                                    launcher: item.launcher.clone(),
+                                    delay_ms: item.delay_ms,
+                                    wait_started: item.wait_started,
+                                    timeout_secs: item.timeout_secs,
+                                })
+                        })
+                        .collect();
+
+                    output::print(format, &records, || {
+                        let mut builder = Builder::default();
+                        builder.push_record([
+                            "Group",
+                            "Step",
+                            "Launcher",
+                            "Delay (ms)",
+                            "Wait started",
+                            "Timeout (s)",
+                        ]);
+                        for record in &records {
+                            builder.push_record([
+                                record.group.clone(),
+                                record.step.to_string(),
+                                record.launcher.clone(),
+                                record.delay_ms.to_string(),
+                                record.wait_started.to_string(),
+                                record.timeout_secs.to_string(),
+                            ]);
+                        }
+                        let mut table = builder.build();
+                        table.with(Style::ascii_rounded());
+                        table.to_string()
+                    })?;
+                    logger_control::log(
+                        "Launcher group show called",
+                        logger_control::LogLevel::INFO,
+                    );
+                }
+                Some(LcGroupCommands::Run { name }) => {
+                    data_controller::init_launcher()?;
+                    run_launcher_group(platform, format, name)?;
+                }
+            },
         },
     }
+
+    Ok(())
 }
 
-fn bytes_to_gb(bytes: u64) -> f64 {
-    let gb = bytes as f64 / (1024.0 * 1024.0 * 1024.0);
-    format!("{:.2}", gb).parse().unwrap_or(0.0)
+fn run_launcher_group(
+    platform: &impl Platform,
+    format: OutputFormat,
+    name: &str,
+) -> Result<(), JweError> {
+    data_controller::init_launcher_groups()?;
+
+    let groups = data_controller::read_launcher_groups()?.groups;
+    let group = groups.get(name).ok_or_else(|| {
+        JweError::NotFound(format!("Launcher or launcher group not found {}", name))
+    })?;
+    let launchers = data_controller::read_launcher()?.launchers;
+
+    let records = launcher::run_group(platform, name, group, &launchers);
+    output::print(format, &records, || {
+        let mut builder = Builder::default();
+        builder.push_record(["Step", "Launcher", "Result", "Message"]);
+        for record in &records {
+            builder.push_record([
+                record.step.to_string(),
+                record.launcher.clone(),
+                if record.success { "ok" } else { "failed" }.to_string(),
+                record.message.clone(),
+            ]);
+        }
+        let mut table = builder.build();
+        table.with(Style::ascii_rounded());
+        table.to_string()
+    })?;
+
+    let failed = records.iter().filter(|record| !record.success).count();
+    if failed > 0 {
+        return Err(JweError::Platform(format!(
+            "{} of {} launchers failed in group {}",
+            failed,
+            records.len(),
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn fav_add(name: Option<&str>, url: Option<&str>) -> Result<(), JweError> {
+    data_controller::init_favorites()?;
+
+    let mut favorites = data_controller::read_favorites()?;
+    let name = prompt::value_or_prompt(name, "Please enter the name of the favorite URL", "name")?;
+    let url = prompt::value_or_prompt(url, "Please enter the URL", "URL")?;
+
+    favorites.favorites.insert(name.clone(), url);
+    data_controller::write_favorites(favorites)?;
+    logger_control::log(
+        &format!("Browser favorite favorite called {}", name),
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
+
+fn fav_remove(name: Option<&str>) -> Result<(), JweError> {
+    data_controller::init_favorites()?;
+
+    let mut favorites_map = data_controller::read_favorites()?.favorites;
+    if name.is_none() && prompt::is_interactive() {
+        let mut keys: Vec<String> = favorites_map.keys().cloned().collect();
+        keys.sort_by_key(|a| a.to_lowercase());
+        let mut builder = Builder::default();
+        builder.push_record(keys);
+        let mut table = builder.build();
+        table.with(Style::ascii_rounded());
+        println!("{}", table);
+    }
+
+    let name = prompt::value_or_prompt(
+        name,
+        "Please enter the name of the favorite URL you would like to remove",
+        "name",
+    )?;
+
+    if favorites_map.remove(&name).is_none() {
+        return Err(JweError::NotFound(format!(
+            "Favorite URL not found {}",
+            name
+        )));
+    }
+    data_controller::write_favorites(data_controller::Favorites {
+        favorites: favorites_map,
+    })?;
+    logger_control::log(
+        &format!("Browser favorite remove_favorite called {}", name),
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
+
+fn fav_list(format: OutputFormat) -> Result<(), JweError> {
+    data_controller::init_favorites()?;
+
+    let favorites_map = data_controller::read_favorites()?.favorites;
+    let mut records: Vec<FavoriteRecord> = favorites_map
+        .into_iter()
+        .map(|(name, url)| FavoriteRecord { name, url })
+        .collect();
+    records.sort_by_key(|record| record.name.to_lowercase());
+
+    output::print(format, &records, || {
+        let keys: Vec<String> = records.iter().map(|record| record.name.clone()).collect();
+        output::grid(&keys)
+    })?;
+    logger_control::log(
+        "Browser favorite list_favorite called",
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
+
+fn fav_open(name: &str, web_search: &str) -> Result<(), JweError> {
+    data_controller::init_favorites()?;
+
+    let favorites_map = data_controller::read_favorites()?.favorites;
+    let favorite_url = favorites_map
+        .get(name)
+        .ok_or_else(|| JweError::NotFound(format!("Favorite URL not found {}", name)))?;
+
+    libs::browser_controller::search_in_browser(favorite_url, web_search)?;
+    logger_control::log(
+        &format!(
+            "Browser favorite open_favorite open_favorite called {}",
+            name
+        ),
+        logger_control::LogLevel::INFO,
+    );
+    Ok(())
+}
+
+// stdout は結果の出力に使うので stderr に出す
+// 設定ファイルが壊れていても設定を使わないコマンドは動くように、警告だけ出して続ける
+fn load_data() {
+    let loaded = [
+        load_settings(),
+        migration::migrate_launchers().map(|report| print_migration_report(&report)),
+    ];
+    for e in loaded.into_iter().filter_map(Result::err) {
+        eprintln!("Warning: {}", e);
+        logger_control::log(
+            &format!("Failed to load data: {}", e),
+            logger_control::LogLevel::WARNING,
+        );
+    }
+}
+
+fn load_settings() -> Result<(), JweError> {
+    data_controller::init_settings()?;
+    print_migration_report(&migration::migrate_settings()?);
+    data_controller::null_search_settings()
+}
+
+// 設定ファイルを型として読まないので、中身が壊れていても動く
+fn config_file_cmd(action: &ConfigCommands) -> Result<(), JweError> {
+    let store = data_controller::settings_store()?;
+    match action {
+        ConfigCommands::Path => println!("{}", store.path().display()),
+        ConfigCommands::Edit => {
+            data_controller::init_settings()?;
+            config_controller::edit(store.path())?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn print_migration_report(report: &MigrationReport) {
+    if report.is_empty() {
+        return;
+    }
+
+    let from = if report.from.is_empty() {
+        "unknown"
+    } else {
+        &report.from
+    };
+    eprintln!("{} migrated from {} to {}", report.name, from, report.to);
+    for migration in &report.applied {
+        eprintln!("  migration: {}", migration);
+    }
+    for change in &report.changes {
+        eprintln!("  {}", change);
+    }
+    if let Some(backup) = &report.backup {
+        eprintln!("  backup: {}", backup.display());
+    }
 }